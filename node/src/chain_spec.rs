@@ -48,8 +48,14 @@ fn get_properties() -> Properties {
 
 fn get_assets() -> Vec<(AssetId, AssetData)> {
     vec![
-        (assets::tok(), AssetData { decimals: 12 }),
-        (assets::usd(), AssetData { decimals: 12 }),
+        (
+            assets::tok(),
+            AssetData::new(b"Coin", b"COIN", 12, 0).expect("static values are valid; qed"),
+        ),
+        (
+            assets::usd(),
+            AssetData::new(b"US Dollar", b"USD", 12, 0).expect("static values are valid; qed"),
+        ),
     ]
 }
 
@@ -77,6 +83,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
                 ],
                 get_assets(),
+                // Oracle feeders
+                vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
+                // Random oracle prices
+                true,
                 true,
             )
         },
@@ -129,6 +139,13 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
                 get_assets(),
+                // Oracle feeders, fed by the offchain worker with the validators' `orac` keys
+                vec![
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_account_id_from_seed::<sr25519::Public>("Bob"),
+                ],
+                // Random oracle prices
+                false,
                 true,
             )
         },
@@ -153,6 +170,8 @@ fn testnet_genesis(
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
     assets: Vec<(AssetId, AssetData)>,
+    feeders: Vec<AccountId>,
+    random_prices: bool,
     _enable_println: bool,
 ) -> GenesisConfig {
     GenesisConfig {
@@ -193,6 +212,11 @@ fn testnet_genesis(
                 })
                 .collect(),
         },
-        oracle: OracleConfig { prices: vec![] },
+        oracle: OracleConfig {
+            prices: vec![],
+            feeders,
+            random_prices,
+            schedule: vec![],
+        },
     }
 }
@@ -0,0 +1,96 @@
+//! `balances_*` RPC methods.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_balances::runtime_api::BalancesApi as BalancesRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait BalancesApi<BlockHash> {
+    /// Returns the total issuance of `asset`, given by its symbol, e.g. `"coin"`.
+    #[rpc(name = "balances_totalIssuance")]
+    fn total_issuance(&self, asset: String, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+    /// Returns the part of the `asset` supply held outside of treasury.
+    #[rpc(name = "balances_circulatingSupply")]
+    fn circulating_supply(&self, asset: String, at: Option<BlockHash>) -> Result<NumberOrHex>;
+}
+
+pub struct Balances<C, Block, AccountId, AssetId, Balance> {
+    client: Arc<C>,
+    _marker: PhantomData<(Block, AccountId, AssetId, Balance)>,
+}
+
+impl<C, Block, AccountId, AssetId, Balance> Balances<C, Block, AccountId, AssetId, Balance> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+fn parse_asset<AssetId: TryFrom<Vec<u8>>>(asset: String) -> Result<AssetId> {
+    AssetId::try_from(asset.into_bytes()).map_err(|_| RpcError {
+        code: ErrorCode::InvalidParams,
+        message: "Invalid asset id".into(),
+        data: None,
+    })
+}
+
+fn runtime_error(message: &str, e: impl ToString) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: message.into(),
+        data: Some(e.to_string().into()),
+    }
+}
+
+impl<C, Block, AccountId, AssetId, Balance> BalancesApi<<Block as BlockT>::Hash>
+    for Balances<C, Block, AccountId, AssetId, Balance>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: BalancesRuntimeApi<Block, AccountId, AssetId, Balance>,
+    AccountId: Codec + Send + Sync + 'static,
+    AssetId: Codec + TryFrom<Vec<u8>> + Send + Sync + 'static,
+    Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+{
+    fn total_issuance(
+        &self,
+        asset: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let asset = parse_asset::<AssetId>(asset)?;
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        self.client
+            .runtime_api()
+            .total_issuance(&at, asset)
+            .map(Into::into)
+            .map_err(|e| runtime_error("Unable to query total issuance", e))
+    }
+
+    fn circulating_supply(
+        &self,
+        asset: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<NumberOrHex> {
+        let asset = parse_asset::<AssetId>(asset)?;
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        self.client
+            .runtime_api()
+            .circulating_supply(&at, asset)
+            .map(Into::into)
+            .map_err(|e| runtime_error("Unable to query circulating supply", e))
+    }
+}
@@ -1,16 +1,46 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{dispatch::DispatchError, traits::Get};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::Get,
+};
+use frame_system::{ensure_signed_or_root, pallet_prelude::OriginFor};
 pub use pallet::*;
-use primitives::assets::AssetGetter;
+use primitives::{
+    assets::{AssetGetter, AssetIndex, AssetMetadata, AssetStatus, AssetTeam},
+    currency::Currency,
+};
+use scale_info::TypeInfo;
 use sp_std::prelude::*;
 
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> = <<T as Config>::Currency as Currency>::Balance;
+
+/// Team of an asset created by a signed origin.
+#[derive(Clone, Debug, Decode, Encode, MaxEncodedLen, PartialEq, Eq, TypeInfo)]
+pub struct AssetDetails<AccountId, Balance> {
+    /// Manages metadata, team and removal of the asset.
+    pub owner: AccountId,
+    /// Mints and burns the asset.
+    pub issuer: AccountId,
+    /// Freezes and thaws the asset.
+    pub freezer: AccountId,
+    /// Main asset deposit reserved from the owner, unreserved once the asset is removed.
+    pub deposit: Balance,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
-    use primitives::assets::OnAssetCreate;
+    use primitives::assets::{OnAssetCreate, OnAssetRemove};
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -21,9 +51,29 @@ pub mod pallet {
             + MaybeSerializeDeserialize
             + MaxEncodedLen
             + TryFrom<Vec<u8>>;
-        type AssetData: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
+        type AssetData: Parameter
+            + Member
+            + MaybeSerializeDeserialize
+            + MaxEncodedLen
+            + AssetMetadata;
         type MainAsset: Get<Self::AssetId>;
         type OnAssetCreate: OnAssetCreate<Self::AssetId>;
+        type OnAssetRemove: OnAssetRemove<Self::AssetId>;
+        /// Maximum number of dependent storage entries cleaned up per block
+        /// while an asset is being destroyed.
+        type RemoveLimit: Get<u32>;
+        type Currency: Currency<
+            AccountId = Self::AccountId,
+            AssetId = Self::AssetId,
+            CurrencyError = DispatchError,
+        >;
+        /// Main asset deposit taken from the creator of a permissionless asset.
+        type AssetDeposit: Get<BalanceOf<Self>>;
+        /// Amount of a permissionless asset minted to its owner on creation,
+        /// the rest of the initial issuance stays in treasury.
+        type OwnerIssuance: Get<BalanceOf<Self>>;
+        /// Maximum number of assets, bounds iteration over all assets.
+        type MaxAssets: Get<u32>;
     }
 
     #[pallet::pallet]
@@ -34,6 +84,38 @@ pub mod pallet {
     pub type Assets<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AssetId, T::AssetData, OptionQuery>;
 
+    /// Team of assets created by an account, kept until a destroyed asset is cleaned up.
+    #[pallet::storage]
+    pub type Details<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        AssetDetails<T::AccountId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Ids of all assets, sorted.
+    #[pallet::storage]
+    pub type AssetList<T: Config> =
+        StorageValue<_, BoundedVec<T::AssetId, T::MaxAssets>, ValueQuery>;
+
+    /// Index assigned to the next created asset.
+    #[pallet::storage]
+    pub type NextAssetIndex<T: Config> = StorageValue<_, AssetIndex, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Indices<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AssetId, AssetIndex, OptionQuery>;
+
+    #[pallet::storage]
+    pub type IdByIndex<T: Config> =
+        StorageMap<_, Twox64Concat, AssetIndex, T::AssetId, OptionQuery>;
+
+    /// Assets in `Destroying` status, cleaned up one at a time in `on_initialize`.
+    #[pallet::storage]
+    pub type DestroyQueue<T: Config> =
+        StorageValue<_, BoundedVec<T::AssetId, T::MaxAssets>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub assets: Vec<(T::AssetId, T::AssetData)>,
@@ -51,6 +133,7 @@ pub mod pallet {
         fn build(&self) {
             for (id, data) in &self.assets {
                 Assets::<T>::insert(id, data);
+                Pallet::<T>::register(id).expect("Asset can't be registered");
             }
         }
     }
@@ -58,8 +141,36 @@ pub mod pallet {
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        Created { id: T::AssetId, data: T::AssetData },
-        Removed { id: T::AssetId, data: T::AssetData },
+        Created {
+            id: T::AssetId,
+            data: T::AssetData,
+        },
+        Destroying {
+            id: T::AssetId,
+        },
+        Removed {
+            id: T::AssetId,
+            data: T::AssetData,
+        },
+        MetadataSet {
+            id: T::AssetId,
+            data: T::AssetData,
+        },
+        OwnerChanged {
+            id: T::AssetId,
+            owner: T::AccountId,
+        },
+        TeamChanged {
+            id: T::AssetId,
+            issuer: T::AccountId,
+            freezer: T::AccountId,
+        },
+        Frozen {
+            id: T::AssetId,
+        },
+        Thawed {
+            id: T::AssetId,
+        },
     }
 
     #[pallet::error]
@@ -67,36 +178,263 @@ pub mod pallet {
         WrongName,
         FailedAssetCreateHook,
         NotFound,
+        BadMetadata,
+        AlreadyExists,
+        AssetFrozen,
+        AssetDestroying,
+        NotFrozen,
+        NoPermission,
+        NoAvailableIndex,
+        TooManyAssets,
+        RemoveMainAsset,
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Creates an asset. A signed origin pays `AssetDeposit` and becomes the asset owner.
+        #[frame_support::transactional]
         #[pallet::weight(10_000)]
         pub fn create(
             origin: OriginFor<T>,
             name: Vec<u8>,
             data: T::AssetData,
         ) -> DispatchResultWithPostInfo {
-            ensure_root(origin)?;
+            let owner = ensure_signed_or_root(origin)?;
 
             let id = T::AssetId::try_from(name).map_err(|_| Error::<T>::WrongName)?;
+            ensure!(!Assets::<T>::contains_key(&id), Error::<T>::AlreadyExists);
+            let mut data = data;
+            data.set_status(AssetStatus::Active);
             Assets::<T>::insert(&id, &data);
+            Self::register(&id)?;
             T::OnAssetCreate::on_asset_create(&id).ok_or(Error::<T>::FailedAssetCreateHook)?;
 
+            if let Some(owner) = owner {
+                let deposit = T::AssetDeposit::get();
+                T::Currency::reserve(&owner, &T::MainAsset::get(), deposit)?;
+                let issuance = T::OwnerIssuance::get();
+                if issuance != Default::default() {
+                    T::Currency::mint(&owner, &id, issuance)?;
+                }
+                Details::<T>::insert(
+                    &id,
+                    AssetDetails {
+                        owner: owner.clone(),
+                        issuer: owner.clone(),
+                        freezer: owner,
+                        deposit,
+                    },
+                );
+            }
+
             Self::deposit_event(Event::Created { id, data });
             Ok(().into())
         }
 
+        /// Starts destroying the asset. The owner gets the deposit back right away,
+        /// the asset team can't act on it anymore.
+        #[frame_support::transactional]
         #[pallet::weight(10_000)]
         pub fn remove(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResultWithPostInfo {
-            ensure_root(origin)?;
+            let id = T::AssetId::try_from(name).map_err(|_| Error::<T>::WrongName)?;
+            ensure!(id != T::MainAsset::get(), Error::<T>::RemoveMainAsset);
+            Self::ensure_team_or_root(origin, &id, |d| &d.owner)?;
+            Self::set_status(&id, AssetStatus::Destroying)?;
+            Details::<T>::try_mutate(&id, |details| {
+                if let Some(details) = details {
+                    let deposit = core::mem::take(&mut details.deposit);
+                    T::Currency::unreserve(&details.owner, &T::MainAsset::get(), deposit)?;
+                }
+                Ok::<_, DispatchError>(())
+            })?;
+            DestroyQueue::<T>::try_append(&id).map_err(|_| Error::<T>::TooManyAssets)?;
 
+            Self::deposit_event(Event::Destroying { id });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn freeze(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResultWithPostInfo {
+            let id = T::AssetId::try_from(name).map_err(|_| Error::<T>::WrongName)?;
+            Self::ensure_team_or_root(origin, &id, |d| &d.freezer)?;
+            Self::set_status(&id, AssetStatus::Frozen)?;
+
+            Self::deposit_event(Event::Frozen { id });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn thaw(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResultWithPostInfo {
             let id = T::AssetId::try_from(name).map_err(|_| Error::<T>::WrongName)?;
-            let data = Assets::<T>::take(&id).ok_or(Error::<T>::NotFound)?;
+            Self::ensure_team_or_root(origin, &id, |d| &d.freezer)?;
+            let data = Self::get(&id)?;
+            ensure!(data.status() == AssetStatus::Frozen, Error::<T>::NotFrozen);
+            Self::set_status(&id, AssetStatus::Active)?;
+
+            Self::deposit_event(Event::Thawed { id });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn set_metadata(
+            origin: OriginFor<T>,
+            asset: Vec<u8>,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            decimals: u8,
+        ) -> DispatchResultWithPostInfo {
+            let id = T::AssetId::try_from(asset).map_err(|_| Error::<T>::WrongName)?;
+            Self::ensure_team_or_root(origin, &id, |d| &d.owner)?;
+            let name = name.try_into().map_err(|_| Error::<T>::BadMetadata)?;
+            let symbol = symbol.try_into().map_err(|_| Error::<T>::BadMetadata)?;
+            let data = Assets::<T>::try_mutate(&id, |data| {
+                let data = data.as_mut().ok_or(Error::<T>::NotFound)?;
+                data.set_metadata(name, symbol, decimals);
+                Ok::<_, Error<T>>(data.clone())
+            })?;
 
-            Self::deposit_event(Event::Removed { id, data });
+            Self::deposit_event(Event::MetadataSet { id, data });
             Ok(().into())
         }
+
+        /// Moves the asset deposit from the current owner to the new one.
+        #[frame_support::transactional]
+        #[pallet::weight(10_000)]
+        pub fn transfer_ownership(
+            origin: OriginFor<T>,
+            asset: Vec<u8>,
+            owner: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let id = T::AssetId::try_from(asset).map_err(|_| Error::<T>::WrongName)?;
+            Self::ensure_team_or_root(origin, &id, |d| &d.owner)?;
+            Details::<T>::try_mutate(&id, |details| {
+                let details = details.as_mut().ok_or(Error::<T>::NotFound)?;
+                if details.owner != owner && details.deposit != Default::default() {
+                    T::Currency::repatriate_reserved(
+                        &details.owner,
+                        &owner,
+                        &T::MainAsset::get(),
+                        details.deposit,
+                    )?;
+                }
+                details.owner = owner.clone();
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::OwnerChanged { id, owner });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn set_team(
+            origin: OriginFor<T>,
+            asset: Vec<u8>,
+            issuer: T::AccountId,
+            freezer: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let id = T::AssetId::try_from(asset).map_err(|_| Error::<T>::WrongName)?;
+            Self::ensure_team_or_root(origin, &id, |d| &d.owner)?;
+            Details::<T>::try_mutate(&id, |details| {
+                let details = details.as_mut().ok_or(Error::<T>::NotFound)?;
+                details.issuer = issuer.clone();
+                details.freezer = freezer.clone();
+                Ok::<_, Error<T>>(())
+            })?;
+
+            Self::deposit_event(Event::TeamChanged {
+                id,
+                issuer,
+                freezer,
+            });
+            Ok(().into())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            let id = match DestroyQueue::<T>::get().first() {
+                Some(id) => id.clone(),
+                None => return weight,
+            };
+
+            let (_, done, used) = T::OnAssetRemove::on_asset_remove(&id, T::RemoveLimit::get());
+            weight = weight.saturating_add(used);
+            if done {
+                weight = weight.saturating_add(T::OnAssetRemove::on_asset_removed(&id));
+                Self::unregister(&id);
+                DestroyQueue::<T>::mutate(|queue| queue.retain(|a| a != &id));
+                Details::<T>::remove(&id);
+                if let Some(data) = Assets::<T>::take(&id) {
+                    Self::deposit_event(Event::Removed { id, data });
+                }
+                // `AssetList`, `Indices`, `IdByIndex`, `DestroyQueue`, `Details` and `Assets`
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 6));
+            }
+            weight
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Adds a new asset to the sorted asset list and assigns it the next free index.
+    /// Indices of removed assets are not reused.
+    fn register(id: &T::AssetId) -> DispatchResult {
+        AssetList::<T>::try_mutate(|list| {
+            let pos = list
+                .binary_search(id)
+                .err()
+                .ok_or(Error::<T>::AlreadyExists)?;
+            list.try_insert(pos, id.clone())
+                .map_err(|_| Error::<T>::TooManyAssets)
+        })?;
+
+        let index = NextAssetIndex::<T>::get();
+        let next = index.checked_add(1).ok_or(Error::<T>::NoAvailableIndex)?;
+        NextAssetIndex::<T>::put(next);
+        Indices::<T>::insert(id, index);
+        IdByIndex::<T>::insert(index, id);
+        Ok(())
+    }
+
+    fn unregister(id: &T::AssetId) {
+        AssetList::<T>::mutate(|list| list.retain(|a| a != id));
+        if let Some(index) = Indices::<T>::take(id) {
+            IdByIndex::<T>::remove(index);
+        }
+    }
+
+    /// Ensures origin is root or the team member of the asset selected by `role`.
+    fn ensure_team_or_root(
+        origin: OriginFor<T>,
+        id: &T::AssetId,
+        role: impl FnOnce(&AssetDetails<T::AccountId, BalanceOf<T>>) -> &T::AccountId,
+    ) -> DispatchResult {
+        Self::check(id)?;
+        if let Some(who) = ensure_signed_or_root(origin)? {
+            let details = Self::team(id).ok_or(Error::<T>::NoPermission)?;
+            ensure!(role(&details) == &who, Error::<T>::NoPermission);
+        }
+        Ok(())
+    }
+
+    /// Team of an asset that is not destroying.
+    fn team(id: &T::AssetId) -> Option<AssetDetails<T::AccountId, BalanceOf<T>>> {
+        Details::<T>::get(id).filter(|_| Self::check(id).is_ok())
+    }
+
+    /// Moves an existing, not destroying asset to `status`.
+    fn set_status(id: &T::AssetId, status: AssetStatus) -> Result<(), DispatchError> {
+        Assets::<T>::try_mutate(id, |data| {
+            let data = data.as_mut().ok_or(Error::<T>::NotFound)?;
+            ensure!(
+                data.status() != AssetStatus::Destroying,
+                Error::<T>::AssetDestroying
+            );
+            data.set_status(status);
+            Ok(())
+        })
     }
 }
 
@@ -109,11 +447,57 @@ impl<T: Config> AssetGetter for Pallet<T> {
         Assets::<T>::get(id).ok_or(Error::<T>::NotFound.into())
     }
 
+    fn check(id: &T::AssetId) -> Result<(), DispatchError> {
+        match Self::get(id)?.status() {
+            AssetStatus::Destroying => Err(Error::<T>::AssetDestroying.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_active(id: &T::AssetId) -> Result<(), DispatchError> {
+        match Self::get(id)?.status() {
+            AssetStatus::Active => Ok(()),
+            AssetStatus::Frozen => Err(Error::<T>::AssetFrozen.into()),
+            AssetStatus::Destroying => Err(Error::<T>::AssetDestroying.into()),
+        }
+    }
+
+    fn index_of(id: &T::AssetId) -> Result<AssetIndex, DispatchError> {
+        Indices::<T>::get(id).ok_or(Error::<T>::NotFound.into())
+    }
+
+    fn id_of(index: AssetIndex) -> Result<T::AssetId, DispatchError> {
+        IdByIndex::<T>::get(index).ok_or(Error::<T>::NotFound.into())
+    }
+
     fn get_main() -> T::AssetId {
         T::MainAsset::get()
     }
 
     fn get_all() -> Vec<T::AssetId> {
-        Assets::<T>::iter_keys().collect()
+        AssetList::<T>::get().into_inner()
+    }
+
+    /// `AssetList` is kept sorted by `Ord` on registration.
+    fn get_all_in_ord() -> Vec<T::AssetId> {
+        Self::get_all()
+    }
+}
+
+impl<T: Config> AssetTeam<T::AccountId, T::AssetId> for Pallet<T> {
+    fn is_owner(who: &T::AccountId, id: &T::AssetId) -> bool {
+        Self::team(id).map_or(false, |d| &d.owner == who)
+    }
+
+    fn is_issuer(who: &T::AccountId, id: &T::AssetId) -> bool {
+        Self::team(id).map_or(false, |d| &d.issuer == who)
+    }
+
+    fn is_freezer(who: &T::AccountId, id: &T::AssetId) -> bool {
+        Self::team(id).map_or(false, |d| &d.freezer == who)
+    }
+
+    fn is_owned(id: &T::AssetId) -> bool {
+        Details::<T>::contains_key(id)
     }
 }
@@ -0,0 +1,128 @@
+use crate as pallet_assets;
+use frame_support::{
+    parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, Everything, GenesisBuild},
+};
+use primitives::{
+    assets::{self, AssetData, AssetId},
+    testing::UnitPrices,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const TREASURY: AccountId = 100;
+pub const INITIAL_BALANCE: u128 = 1_000_000_000_000;
+pub const ASSET_DEPOSIT: u128 = 100;
+pub const OWNER_ISSUANCE: u128 = 1_000_000;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub TokAsset: AssetId = assets::tok();
+    pub const TreasuryModuleId: AccountId = TREASURY;
+    pub const AuthorFeeShare: Perbill = Perbill::from_percent(20);
+}
+
+impl pallet_assets::Config for Test {
+    type Event = Event;
+    type AssetId = AssetId;
+    type AssetData = AssetData;
+    type MainAsset = TokAsset;
+    type OnAssetCreate = (Balances,);
+    type OnAssetRemove = (Balances,);
+    type RemoveLimit = ConstU32<1>;
+    type Currency = Balances;
+    type AssetDeposit = ConstU128<ASSET_DEPOSIT>;
+    type OwnerIssuance = ConstU128<OWNER_ISSUANCE>;
+    type MaxAssets = ConstU32<3>;
+}
+
+impl pallet_balances::Config for Test {
+    type Event = Event;
+    type Balance = u128;
+    type Assets = Assets;
+    type AssetTeam = Assets;
+    type Prices = UnitPrices;
+    type TreasuryModuleId = TreasuryModuleId;
+    type InitialAssetIssuance = ConstU128<1_000_000_000_000_000>;
+    type MaxLocks = ConstU32<4>;
+    type MaxBatchTransfers = ConstU32<4>;
+    type FindAuthor = ();
+    type AuthorFeeShare = AuthorFeeShare;
+    type OnBalanceChanged = ();
+}
+
+pub fn asset_data() -> AssetData {
+    AssetData::new(b"Test asset", b"TST", 9, 0).expect("static value is valid; qed")
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_assets::GenesisConfig::<Test> {
+        assets: vec![(assets::tok(), asset_data())],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        treasury: vec![assets::tok()],
+        balances: vec![
+            (ALICE, vec![(assets::tok(), INITIAL_BALANCE)]),
+            (BOB, vec![(assets::tok(), INITIAL_BALANCE)]),
+        ],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,384 @@
+use crate::{mock::*, AssetDetails, Details, Error, Event as AssetsEvent};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use primitives::{
+    assets::{self, AssetData, AssetGetter, AssetId, AssetMetadata, AssetStatus},
+    currency::Currency,
+};
+
+fn gold() -> AssetId {
+    AssetId::from_utf8("gold").expect("static value is valid; qed")
+}
+
+fn create_gold() {
+    assert_ok!(Assets::create(
+        Origin::root(),
+        b"gold".to_vec(),
+        asset_data()
+    ));
+}
+
+#[test]
+fn create_stores_metadata() {
+    new_test_ext().execute_with(|| {
+        let data = AssetData::new(b"Gold", b"GLD", 6, 10).unwrap();
+        assert_ok!(Assets::create(
+            Origin::root(),
+            b"gold".to_vec(),
+            data.clone()
+        ));
+
+        assert_eq!(Assets::get(&gold()), Ok(data.clone()));
+        System::assert_last_event(AssetsEvent::Created { id: gold(), data }.into());
+    });
+}
+
+#[test]
+fn create_rejects_wrong_and_taken_names() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Assets::create(Origin::root(), b"too long name".to_vec(), asset_data()),
+            Error::<Test>::WrongName
+        );
+        assert_noop!(
+            Assets::create(Origin::root(), b"tok".to_vec(), asset_data()),
+            Error::<Test>::AlreadyExists
+        );
+    });
+}
+
+#[test]
+fn set_metadata_updates_and_validates_metadata() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::root(),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+
+        assert_noop!(
+            Assets::set_metadata(
+                Origin::root(),
+                b"gold".to_vec(),
+                vec![b'g'; 33],
+                b"GLD".to_vec(),
+                6
+            ),
+            Error::<Test>::BadMetadata
+        );
+        assert_ok!(Assets::set_metadata(
+            Origin::root(),
+            b"gold".to_vec(),
+            b"Gold".to_vec(),
+            b"GLD".to_vec(),
+            6
+        ));
+
+        let data = AssetData::new(b"Gold", b"GLD", 6, 0).unwrap();
+        assert_eq!(Assets::get(&gold()), Ok(data.clone()));
+        System::assert_last_event(AssetsEvent::MetadataSet { id: gold(), data }.into());
+    });
+}
+
+#[test]
+fn frozen_assets_cannot_be_transferred() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &gold(), 1_000));
+
+        assert_ok!(Assets::freeze(Origin::root(), b"gold".to_vec()));
+        System::assert_last_event(AssetsEvent::Frozen { id: gold() }.into());
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, gold(), 100),
+            Error::<Test>::AssetFrozen
+        );
+
+        assert_ok!(Assets::thaw(Origin::root(), b"gold".to_vec()));
+        System::assert_last_event(AssetsEvent::Thawed { id: gold() }.into());
+        assert_noop!(
+            Assets::thaw(Origin::root(), b"gold".to_vec()),
+            Error::<Test>::NotFrozen
+        );
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, gold(), 100));
+    });
+}
+
+#[test]
+fn remove_destroys_asset_over_blocks() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &gold(), 1_000));
+        assert_ok!(<Balances as Currency>::mint(&BOB, &gold(), 1_000));
+
+        assert_ok!(Assets::remove(Origin::root(), b"gold".to_vec()));
+        System::assert_last_event(AssetsEvent::Destroying { id: gold() }.into());
+        assert_eq!(
+            Assets::get(&gold()).unwrap().status(),
+            AssetStatus::Destroying
+        );
+        assert_noop!(
+            Assets::freeze(Origin::root(), b"gold".to_vec()),
+            Error::<Test>::AssetDestroying
+        );
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, gold(), 100),
+            Error::<Test>::AssetDestroying
+        );
+
+        // `RemoveLimit` sweeps one holder per block
+        Assets::on_initialize(2);
+        assert!(Assets::get(&gold()).is_ok());
+        Assets::on_initialize(3);
+        System::assert_last_event(
+            AssetsEvent::Removed {
+                id: gold(),
+                data: AssetData {
+                    status: AssetStatus::Destroying,
+                    ..asset_data()
+                },
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get(&gold()), Err(Error::<Test>::NotFound.into()));
+        assert_eq!(Assets::get_all(), vec![assets::tok()]);
+    });
+}
+
+#[test]
+fn main_asset_cannot_be_removed() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Assets::remove(Origin::root(), b"tok".to_vec()),
+            Error::<Test>::RemoveMainAsset
+        );
+    });
+}
+
+#[test]
+fn remove_sweeps_balances_of_the_asset() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        let index = Assets::index_of(&gold()).unwrap();
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &gold(), 1_000));
+        assert_ok!(<Balances as Currency>::mint(&BOB, &gold(), 1_000));
+        assert_ok!(Assets::remove(Origin::root(), b"gold".to_vec()));
+
+        Assets::on_initialize(2);
+        // Treasury and the holder left for the next block
+        assert_eq!(
+            pallet_balances::AssetAccounts::<Test>::iter_key_prefix(index).count(),
+            2
+        );
+
+        Assets::on_initialize(3);
+        assert_eq!(
+            pallet_balances::AssetAccounts::<Test>::iter_key_prefix(index).count(),
+            0
+        );
+        assert!(!pallet_balances::Accounts::<Test>::contains_key(
+            ALICE, index
+        ));
+        assert!(!pallet_balances::Accounts::<Test>::contains_key(BOB, index));
+        assert!(!pallet_balances::TotalIssuance::<Test>::contains_key(index));
+        assert_eq!(pallet_balances::HolderCount::<Test>::get(index), 0);
+    });
+}
+
+#[test]
+fn signed_create_reserves_deposit_until_removal() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+        assert_eq!(
+            Details::<Test>::get(&gold()),
+            Some(AssetDetails {
+                owner: ALICE,
+                issuer: ALICE,
+                freezer: ALICE,
+                deposit: ASSET_DEPOSIT,
+            })
+        );
+        assert_eq!(
+            Balances::reserved_balance(&ALICE, &assets::tok()),
+            ASSET_DEPOSIT
+        );
+        assert_eq!(Balances::balance(&ALICE, &gold()), OWNER_ISSUANCE);
+
+        assert_ok!(Assets::remove(Origin::signed(ALICE), b"gold".to_vec()));
+        assert_eq!(Balances::reserved_balance(&ALICE, &assets::tok()), 0);
+        assert_eq!(Balances::balance(&ALICE, &assets::tok()), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn signed_create_fails_without_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Assets::create(Origin::signed(3), b"gold".to_vec(), asset_data()),
+            pallet_balances::Error::<Test>::NoAccount
+        );
+    });
+}
+
+#[test]
+fn team_roles_are_enforced() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+        assert_noop!(
+            Assets::set_team(Origin::signed(BOB), b"gold".to_vec(), BOB, BOB),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(Assets::set_team(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            BOB,
+            3
+        ));
+        System::assert_last_event(
+            AssetsEvent::TeamChanged {
+                id: gold(),
+                issuer: BOB,
+                freezer: 3,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            Balances::mint(Origin::signed(ALICE), ALICE, gold(), 10),
+            pallet_balances::Error::<Test>::NoPermission
+        );
+        assert_ok!(Balances::mint(Origin::signed(BOB), BOB, gold(), 10));
+        assert_noop!(
+            Assets::freeze(Origin::signed(ALICE), b"gold".to_vec()),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(Assets::freeze(Origin::signed(3), b"gold".to_vec()));
+    });
+}
+
+#[test]
+fn root_assets_have_no_team() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        assert_noop!(
+            Assets::freeze(Origin::signed(ALICE), b"gold".to_vec()),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            Assets::remove(Origin::signed(ALICE), b"gold".to_vec()),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn indices_of_removed_assets_are_not_reused() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        assert_eq!(Assets::index_of(&gold()), Ok(1));
+        assert_eq!(Assets::id_of(1), Ok(gold()));
+
+        assert_ok!(Assets::remove(Origin::root(), b"gold".to_vec()));
+        Assets::on_initialize(2);
+        assert_eq!(Assets::id_of(1), Err(Error::<Test>::NotFound.into()));
+
+        create_gold();
+        assert_eq!(Assets::index_of(&gold()), Ok(2));
+    });
+}
+
+#[test]
+fn asset_list_is_sorted_and_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(Origin::root(), b"xy".to_vec(), asset_data()));
+        create_gold();
+
+        let xy = AssetId::from_utf8("xy").unwrap();
+        assert_eq!(Assets::get_all(), vec![gold(), assets::tok(), xy]);
+        assert_eq!(Assets::get_all_in_ord(), Assets::get_all());
+        assert_noop!(
+            Assets::create(Origin::root(), b"btc".to_vec(), asset_data()),
+            Error::<Test>::TooManyAssets
+        );
+    });
+}
+
+#[test]
+fn ownership_transfer_moves_the_deposit() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        assert_ok!(Assets::create(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+
+        assert_ok!(Assets::transfer_ownership(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            BOB
+        ));
+        System::assert_last_event(
+            AssetsEvent::OwnerChanged {
+                id: gold(),
+                owner: BOB,
+            }
+            .into(),
+        );
+        assert_eq!(Details::<Test>::get(&gold()).unwrap().owner, BOB);
+        assert_eq!(Balances::reserved_balance(&ALICE, &main), 0);
+        assert_eq!(
+            Balances::balance(&ALICE, &main),
+            INITIAL_BALANCE - ASSET_DEPOSIT
+        );
+        assert_eq!(Balances::reserved_balance(&BOB, &main), ASSET_DEPOSIT);
+
+        // The deposit is returned even while the main asset is frozen
+        assert_ok!(Assets::freeze(Origin::root(), b"tok".to_vec()));
+        assert_ok!(Assets::remove(Origin::signed(BOB), b"gold".to_vec()));
+        assert_eq!(Balances::reserved_balance(&BOB, &main), 0);
+        assert_eq!(
+            Balances::balance(&BOB, &main),
+            INITIAL_BALANCE + ASSET_DEPOSIT
+        );
+    });
+}
+
+#[test]
+fn removal_keeps_accounts_other_consumers_depend_on() {
+    new_test_ext().execute_with(|| {
+        create_gold();
+        assert_ok!(<Balances as Currency>::mint(&3, &gold(), 1_000));
+        assert_ok!(System::inc_consumers(&3));
+
+        assert_ok!(Assets::remove(Origin::root(), b"gold".to_vec()));
+        Assets::on_initialize(2);
+        assert_eq!(System::providers(&3), 1);
+        assert_eq!(pallet_balances::KeptProviders::<Test>::get(3), 1);
+
+        // The next balance of the account reuses the kept reference
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            3,
+            assets::tok(),
+            100
+        ));
+        assert_eq!(System::providers(&3), 1);
+        assert_eq!(pallet_balances::KeptProviders::<Test>::get(3), 0);
+
+        System::dec_consumers(&3);
+        assert_ok!(Balances::transfer(
+            Origin::signed(3),
+            ALICE,
+            assets::tok(),
+            100
+        ));
+        assert_eq!(System::providers(&3), 0);
+    });
+}
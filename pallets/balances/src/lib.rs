@@ -1,23 +1,70 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
     dispatch::{DispatchResult, DispatchResultWithPostInfo},
-    traits::Get,
+    ensure,
+    storage::{PrefixIterator, StoragePrefixedMap},
+    traits::{FindAuthor, Get, LockIdentifier},
+    weights::Weight,
+    Blake2_128Concat, BoundedVec, StorageHasher, Twox64Concat,
 };
+use frame_system::{ensure_signed_or_root, pallet_prelude::OriginFor};
 pub use pallet::*;
 use primitives::{
-    assets::{AssetGetter, OnAssetCreate},
-    currency::Currency,
+    assets::{AssetGetter, AssetIndex, AssetMetadata, AssetTeam, OnAssetCreate, OnAssetRemove},
+    currency::{BalancesPage, Currency, OnBalanceChanged},
     prices::PriceGetter,
 };
+use scale_info::TypeInfo;
 use sp_runtime::{
-    traits::{CheckedAdd, CheckedSub, Zero},
-    transaction_validity::InvalidTransaction,
-    DispatchError,
+    traits::{
+        CheckedAdd, CheckedSub, One, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero,
+    },
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+    DispatchError, Perbill,
 };
+use sp_std::prelude::*;
+
+pub mod payment;
+pub mod runtime_api;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use payment::ChargeAssetTxPayment;
+
+/// `InvalidTransaction::Custom` code for a corrected fee exceeding the withdrawn one.
+pub const FEE_EXCEEDS_WITHDRAWN: u8 = 1;
 
 type AssetIdOf<T> = <<T as Config>::Assets as primitives::assets::AssetGetter>::AssetId;
 
+/// Balance of an account in a single asset.
+#[derive(Clone, Copy, Debug, Default, Decode, Encode, MaxEncodedLen, PartialEq, Eq, TypeInfo)]
+pub struct AssetBalance<Balance> {
+    /// Spendable balance.
+    pub free: Balance,
+    /// Balance set aside by `reserve`, can't be spent until unreserved.
+    pub reserved: Balance,
+    /// Largest of the account locks, `free` can't go below it.
+    pub frozen: Balance,
+}
+
+impl<Balance: Saturating + Copy> AssetBalance<Balance> {
+    pub fn total(&self) -> Balance {
+        self.free.saturating_add(self.reserved)
+    }
+}
+
+/// Named lock on a part of the free balance.
+#[derive(Clone, Debug, Decode, Encode, MaxEncodedLen, PartialEq, Eq, TypeInfo)]
+pub struct BalanceLock<Balance> {
+    pub id: LockIdentifier,
+    pub amount: Balance,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -35,6 +82,7 @@ pub mod pallet {
             + MaybeSerializeDeserialize
             + MaxEncodedLen;
         type Assets: AssetGetter<AssetError = DispatchError>;
+        type AssetTeam: AssetTeam<Self::AccountId, AssetIdOf<Self>>;
         type Prices: PriceGetter<
             AssetId = AssetIdOf<Self>,
             Balance = Self::Balance,
@@ -42,6 +90,18 @@ pub mod pallet {
         >;
         type TreasuryModuleId: Get<Self::AccountId>;
         type InitialAssetIssuance: Get<Self::Balance>;
+        /// Maximum number of locks per account and asset.
+        type MaxLocks: Get<u32>;
+        /// Maximum number of transfers in a single `transfer_batch` call.
+        #[pallet::constant]
+        type MaxBatchTransfers: Get<u32>;
+        /// Author of the current block, credited with tips and its share of fees.
+        type FindAuthor: FindAuthor<Self::AccountId>;
+        /// Share of transaction fees, excluding tips, paid to the block author.
+        #[pallet::constant]
+        type AuthorFeeShare: Get<Perbill>;
+        /// Called on every change of an account balance.
+        type OnBalanceChanged: OnBalanceChanged<Self::AccountId, AssetIdOf<Self>, Self::Balance>;
     }
 
     #[pallet::pallet]
@@ -53,12 +113,95 @@ pub mod pallet {
         _,
         Blake2_128Concat,
         T::AccountId,
+        Twox64Concat,
+        AssetIndex,
+        AssetBalance<T::Balance>,
+        ValueQuery,
+    >;
+
+    /// Holders of each asset ordered by total balance, largest first. The second key is
+    /// the `holder_rank` of the balance.
+    #[pallet::storage]
+    pub type HoldersByBalance<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, AssetIndex>,
+            NMapKey<Identity, [u8; 16]>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        (),
+        OptionQuery,
+    >;
+
+    /// Provider references of purged entries, left to keep alive accounts other consumers
+    /// depend on. Reused by new entries of the account or released once it's not needed.
+    #[pallet::storage]
+    pub type KeptProviders<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Accounts with an `Accounts` entry, keyed by asset first to iterate holders of an asset.
+    #[pallet::storage]
+    pub type AssetAccounts<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetIndex,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    pub type Locks<T: Config> = StorageDoubleMap<
+        _,
         Blake2_128Concat,
-        AssetIdOf<T>,
+        T::AccountId,
+        Twox64Concat,
+        AssetIndex,
+        BoundedVec<BalanceLock<T::Balance>, T::MaxLocks>,
+        ValueQuery,
+    >;
+
+    /// Amounts spenders may transfer from owner balances, keyed by (owner, asset, spender).
+    #[pallet::storage]
+    pub type Approvals<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Twox64Concat, AssetIndex>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
         T::Balance,
         ValueQuery,
     >;
 
+    /// Accounts frozen by root or the asset freezer, keyed by (asset, account).
+    #[pallet::storage]
+    pub type FrozenAccounts<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetIndex,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Sum of all balances of an asset, treasury included.
+    /// Set by the initial issuance, changed by the `mint` and `burn` calls.
+    #[pallet::storage]
+    pub type TotalIssuance<T: Config> =
+        StorageMap<_, Twox64Concat, AssetIndex, T::Balance, ValueQuery>;
+
+    /// Part of the total issuance held outside of treasury.
+    #[pallet::storage]
+    pub type CirculatingSupply<T: Config> =
+        StorageMap<_, Twox64Concat, AssetIndex, T::Balance, ValueQuery>;
+
+    /// Number of accounts with a non-zero total balance of an asset.
+    #[pallet::storage]
+    pub type HolderCount<T: Config> = StorageMap<_, Twox64Concat, AssetIndex, u32, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub treasury: Vec<AssetIdOf<T>>,
@@ -78,15 +221,16 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
-            let treasury = T::TreasuryModuleId::get();
-            let balance = T::InitialAssetIssuance::get();
             for asset in &self.treasury {
-                Accounts::<T>::insert(&treasury, asset, balance);
+                let index = T::Assets::index_of(asset).expect("Unknown treasury asset");
+                Pallet::<T>::init_issuance(index)
+                    .expect("Initial issuance of a treasury asset can't be added in genesis");
             }
 
             for (who, balances) in &self.balances {
                 for (asset, balance) in balances {
-                    let _ = <Pallet<T> as Currency>::mint(who, asset, *balance);
+                    <Pallet<T> as Currency>::mint(who, asset, *balance)
+                        .expect("Genesis balance can't be minted from treasury");
                 }
             }
         }
@@ -116,6 +260,42 @@ pub mod pallet {
             asset: AssetIdOf<T>,
             amount: T::Balance,
         },
+        Approved {
+            owner: T::AccountId,
+            spender: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+        },
+        ApprovalCancelled {
+            owner: T::AccountId,
+            spender: T::AccountId,
+            asset: AssetIdOf<T>,
+        },
+        AssetTransactionPayment {
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+            fee: T::Balance,
+            tip: T::Balance,
+        },
+        TransferBatch {
+            from: T::AccountId,
+            count: u32,
+        },
+        TransferredApproved {
+            spender: T::AccountId,
+            owner: T::AccountId,
+            to: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+        },
+        Frozen {
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+        },
+        Thawed {
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+        },
     }
 
     #[pallet::error]
@@ -123,10 +303,32 @@ pub mod pallet {
         Debt,
         Overflow,
         EmptyTreasury,
+        NoPermission,
+        LiquidityRestrictions,
+        InsufficientReserved,
+        TooManyLocks,
+        ExistentialDeposit,
+        NoAccount,
+        Unapproved,
+        TooManyTransfers,
+        KeepAlive,
+        AccountFrozen,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            Pallet::<T>::check_issuance()
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Issues `amount` of new supply to `who`, increasing the total issuance.
+        /// Unlike `Currency::mint`, which pays out of treasury, nothing is taken from
+        /// another account.
+        #[frame_support::transactional]
         #[pallet::weight(10_000)]
         pub fn mint(
             origin: OriginFor<T>,
@@ -134,12 +336,15 @@ pub mod pallet {
             asset: AssetIdOf<T>,
             amount: T::Balance,
         ) -> DispatchResultWithPostInfo {
-            ensure_root(origin)?;
-            <Self as Currency>::mint(&who, &asset, amount)?;
+            Self::ensure_issuer_or_root(origin, &asset)?;
+            Self::issue(&who, &asset, amount)?;
             Self::deposit_event(Event::<T>::Minted { who, asset, amount });
             Ok(().into())
         }
 
+        /// Destroys `amount` of the `who` balance, decreasing the total issuance.
+        /// Unlike `Currency::burn`, which moves the amount to treasury, it leaves the supply.
+        #[frame_support::transactional]
         #[pallet::weight(10_000)]
         pub fn burn(
             origin: OriginFor<T>,
@@ -147,8 +352,8 @@ pub mod pallet {
             asset: AssetIdOf<T>,
             amount: T::Balance,
         ) -> DispatchResultWithPostInfo {
-            ensure_root(origin)?;
-            <Self as Currency>::burn(&who, &asset, amount)?;
+            Self::ensure_issuer_or_root(origin, &asset)?;
+            Self::destroy(&who, &asset, amount)?;
             Self::deposit_event(Event::<T>::Burnt { who, asset, amount });
             Ok(().into())
         }
@@ -170,31 +375,639 @@ pub mod pallet {
             });
             Ok(().into())
         }
+
+        /// Same as `transfer`, but fails if the sender account would be reaped.
+        #[frame_support::transactional]
+        #[pallet::weight(10_000)]
+        pub fn transfer_keep_alive(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            <Self as Currency>::transfer(&from, &to, &asset, amount)?;
+            let index = T::Assets::index_of(&asset)?;
+            ensure!(
+                Accounts::<T>::contains_key(&from, index),
+                Error::<T>::KeepAlive
+            );
+            Self::deposit_event(Event::<T>::Transfer {
+                from,
+                to,
+                asset,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        /// Transfers the whole spendable balance of `asset`. Transaction fees are withdrawn
+        /// before dispatch, so for the main asset the transferred amount already excludes them.
+        /// With `keep_alive` the sender keeps the asset minimum balance.
+        #[pallet::weight(10_000)]
+        pub fn transfer_all(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            asset: AssetIdOf<T>,
+            keep_alive: bool,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            let index = T::Assets::index_of(&asset)?;
+            let amount = Self::reducible_balance(&from, index, keep_alive);
+            <Self as Currency>::transfer(&from, &to, &asset, amount)?;
+            Self::deposit_event(Event::<T>::Transfer {
+                from,
+                to,
+                asset,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        /// Executes all transfers or none of them.
+        #[frame_support::transactional]
+        #[pallet::weight((transfers.len() as Weight).saturating_mul(10_000))]
+        pub fn transfer_batch(
+            origin: OriginFor<T>,
+            transfers: Vec<(T::AccountId, AssetIdOf<T>, T::Balance)>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            ensure!(
+                transfers.len() as u32 <= T::MaxBatchTransfers::get(),
+                Error::<T>::TooManyTransfers
+            );
+            let count = transfers.len() as u32;
+            for (to, asset, amount) in transfers {
+                <Self as Currency>::transfer(&from, &to, &asset, amount)?;
+            }
+            Self::deposit_event(Event::<T>::TransferBatch { from, count });
+            Ok(().into())
+        }
+
+        /// Allows `spender` to transfer up to `amount` of the caller's `asset`,
+        /// replacing the previous approval.
+        #[pallet::weight(10_000)]
+        pub fn approve(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+            <Self as Currency>::approve(&owner, &spender, &asset, amount)?;
+            Self::deposit_event(Event::<T>::Approved {
+                owner,
+                spender,
+                asset,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            to: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let spender = ensure_signed(origin)?;
+            <Self as Currency>::transfer_from(&spender, &owner, &to, &asset, amount)?;
+            Self::deposit_event(Event::<T>::TransferredApproved {
+                spender,
+                owner,
+                to,
+                asset,
+                amount,
+            });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn cancel_approval(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            asset: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+            <Self as Currency>::cancel_approval(&owner, &spender, &asset)?;
+            Self::deposit_event(Event::<T>::ApprovalCancelled {
+                owner,
+                spender,
+                asset,
+            });
+            Ok(().into())
+        }
+
+        /// Stops `who` from sending or burning `asset`, receiving is still allowed.
+        #[pallet::weight(10_000)]
+        pub fn freeze(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_freezer_or_root(origin, &asset)?;
+            let index = T::Assets::index_of(&asset)?;
+            FrozenAccounts::<T>::insert(index, &who, ());
+            Self::deposit_event(Event::<T>::Frozen { who, asset });
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn thaw(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_freezer_or_root(origin, &asset)?;
+            let index = T::Assets::index_of(&asset)?;
+            FrozenAccounts::<T>::remove(index, &who);
+            Self::deposit_event(Event::<T>::Thawed { who, asset });
+            Ok(().into())
+        }
     }
 }
 
 impl<T: Config> Pallet<T> {
-    fn add(who: &T::AccountId, asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
-        Accounts::<T>::try_mutate(who, asset, |balance| {
-            *balance = balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-            Ok(())
-        })
+    fn ensure_issuer_or_root(origin: OriginFor<T>, asset: &AssetIdOf<T>) -> DispatchResult {
+        if let Some(who) = ensure_signed_or_root(origin)? {
+            ensure!(
+                T::AssetTeam::is_issuer(&who, asset),
+                Error::<T>::NoPermission
+            );
+        }
+        Ok(())
+    }
+
+    fn ensure_freezer_or_root(origin: OriginFor<T>, asset: &AssetIdOf<T>) -> DispatchResult {
+        if let Some(who) = ensure_signed_or_root(origin)? {
+            ensure!(
+                T::AssetTeam::is_freezer(&who, asset),
+                Error::<T>::NoPermission
+            );
+        }
+        Ok(())
     }
 
-    fn sub(who: &T::AccountId, asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
-        Accounts::<T>::try_mutate(who, asset, |balance| {
-            *balance = balance.checked_sub(&amount).ok_or(Error::<T>::Debt)?;
+    fn ensure_not_frozen(who: &T::AccountId, asset: AssetIndex) -> DispatchResult {
+        ensure!(
+            !FrozenAccounts::<T>::contains_key(asset, who),
+            Error::<T>::AccountFrozen
+        );
+        Ok(())
+    }
+
+    /// Adds to free balance, a new account entry takes a provider reference.
+    fn add(who: &T::AccountId, asset: AssetIndex, amount: T::Balance) -> DispatchResult {
+        let (created, old, new) = Accounts::<T>::try_mutate_exists(who, asset, |maybe_balance| {
+            let created = maybe_balance.is_none();
+            let balance = maybe_balance.get_or_insert_with(Default::default);
+            let old = balance.total();
+            balance.free = balance
+                .free
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            Ok::<_, DispatchError>((created, old, balance.total()))
+        })?;
+        if created {
+            Self::take_provider(who);
+            AssetAccounts::<T>::insert(asset, who, ());
+        }
+        Self::balance_changed(who, asset, old, new);
+        Ok(())
+    }
+
+    fn sub(who: &T::AccountId, asset: AssetIndex, amount: T::Balance) -> DispatchResult {
+        Self::mutate_account(who, asset, |balance| {
+            balance.free = Self::free_after_withdrawal(balance, amount)?;
             Ok(())
         })
     }
 
-    fn treasury_add(asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
-        Self::add(&T::TreasuryModuleId::get(), asset, amount)
+    /// Mutates an existing account entry, notifying `T::OnBalanceChanged`.
+    fn mutate_account(
+        who: &T::AccountId,
+        asset: AssetIndex,
+        f: impl FnOnce(&mut AssetBalance<T::Balance>) -> DispatchResult,
+    ) -> DispatchResult {
+        let (old, new) = Accounts::<T>::try_mutate_exists(who, asset, |maybe_balance| {
+            let balance = maybe_balance.as_mut().ok_or(Error::<T>::NoAccount)?;
+            let old = balance.total();
+            f(balance)?;
+            Ok::<_, DispatchError>((old, balance.total()))
+        })?;
+        Self::balance_changed(who, asset, old, new);
+        Ok(())
+    }
+
+    fn balance_changed(who: &T::AccountId, asset: AssetIndex, old: T::Balance, new: T::Balance) {
+        match (old.is_zero(), new.is_zero()) {
+            (true, false) => {
+                HolderCount::<T>::mutate(asset, |count| *count = count.saturating_add(1))
+            }
+            (false, true) => {
+                HolderCount::<T>::mutate(asset, |count| *count = count.saturating_sub(1))
+            }
+            _ => {}
+        }
+        if old != new {
+            if !old.is_zero() {
+                HoldersByBalance::<T>::remove((asset, Self::holder_rank(old), who));
+            }
+            if !new.is_zero() {
+                HoldersByBalance::<T>::insert((asset, Self::holder_rank(new), who), ());
+            }
+        }
+        if let Ok(id) = T::Assets::id_of(asset) {
+            T::OnBalanceChanged::on_balance_changed(who, &id, &old, &new);
+        }
+    }
+
+    /// `HoldersByBalance` key of a total balance, larger balances sort first.
+    fn holder_rank(total: T::Balance) -> [u8; 16] {
+        let total: u128 = total.unique_saturated_into();
+        (u128::MAX - total).to_be_bytes()
+    }
+
+    fn min_balance(asset: AssetIndex) -> T::Balance {
+        T::Assets::id_of(asset)
+            .and_then(|id| T::Assets::get(&id))
+            .map(|data| T::Balance::unique_saturated_from(data.min_balance()))
+            .unwrap_or_else(|_| T::Balance::zero())
     }
 
-    fn treasury_sub(asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
+    /// Ensures a new account entry receives at least the asset minimum balance.
+    fn ensure_existential(
+        who: &T::AccountId,
+        asset: AssetIndex,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        if !Accounts::<T>::contains_key(who, asset) {
+            ensure!(
+                !amount.is_zero() && amount >= Self::min_balance(asset),
+                Error::<T>::ExistentialDeposit
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes the account entry if its total balance fell below the asset minimum balance,
+    /// the dust goes to treasury.
+    #[frame_support::transactional]
+    fn reap_dust(who: &T::AccountId, asset: AssetIndex) -> DispatchResult {
+        if who == &T::TreasuryModuleId::get() {
+            return Ok(());
+        }
+        let total = match Accounts::<T>::try_get(who, asset) {
+            Ok(balance) => balance.total(),
+            Err(_) => return Ok(()),
+        };
+        if total.is_zero() || total < Self::min_balance(asset) {
+            Self::remove_account(who, asset, false)?;
+            if !total.is_zero() {
+                Self::treasury_add(asset, total)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the account entry with its locks and approvals, releasing the references
+    /// taken for them. Fails without changes while other consumers depend on the account,
+    /// unless `keep_provider` leaves its provider reference to keep it alive.
+    #[frame_support::transactional]
+    fn remove_account(
+        who: &T::AccountId,
+        asset: AssetIndex,
+        keep_provider: bool,
+    ) -> DispatchResult {
+        let _ = Approvals::<T>::remove_prefix((who, asset), None);
+        if Locks::<T>::contains_key(who, asset) {
+            Locks::<T>::remove(who, asset);
+            frame_system::Pallet::<T>::dec_consumers(who);
+        }
+        if let Ok(balance) = Accounts::<T>::try_get(who, asset) {
+            Accounts::<T>::remove(who, asset);
+            AssetAccounts::<T>::remove(asset, who);
+            if keep_provider {
+                KeptProviders::<T>::mutate(who, |kept| *kept = kept.saturating_add(1));
+            } else {
+                frame_system::Pallet::<T>::dec_providers(who)?;
+                Self::release_kept_providers(who);
+            }
+            Self::balance_changed(who, asset, balance.total(), T::Balance::zero());
+        }
+        Ok(())
+    }
+
+    /// Takes a provider reference for a new account entry, reusing one kept by `purge_account`.
+    fn take_provider(who: &T::AccountId) {
+        let reused = KeptProviders::<T>::try_mutate_exists(who, |kept| match kept {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                Ok(())
+            }
+            Some(_) => {
+                *kept = None;
+                Ok(())
+            }
+            None => Err(()),
+        });
+        if reused.is_err() {
+            frame_system::Pallet::<T>::inc_providers(who);
+        }
+    }
+
+    /// Releases provider references kept by `purge_account` unless they're the last
+    /// ones keeping the account alive for its consumers.
+    fn release_kept_providers(who: &T::AccountId) {
+        KeptProviders::<T>::mutate_exists(who, |kept| {
+            while let Some(count) = *kept {
+                if frame_system::Pallet::<T>::dec_providers(who).is_err() {
+                    break;
+                }
+                *kept = count.checked_sub(1).filter(|count| *count > 0);
+            }
+        });
+    }
+
+    /// Removes an entry of a removed asset, which can't be rejected.
+    /// Accounts other consumers depend on are kept alive by the entry provider reference.
+    fn purge_account(who: &T::AccountId, asset: AssetIndex) {
+        if Self::remove_account(who, asset, false).is_err() {
+            let _ = Self::remove_account(who, asset, true);
+        }
+    }
+
+    /// Settles `corrected_fee` (tip included) out of the `paid` amount and refunds the rest.
+    /// The tip and `AuthorFeeShare` of the fee go to the block author, the remainder to treasury.
+    fn deposit_fee(
+        who: &T::AccountId,
+        asset: AssetIndex,
+        paid: T::Balance,
+        corrected_fee: T::Balance,
+        tip: T::Balance,
+    ) -> Result<(), TransactionValidityError> {
+        let refund_amount = paid
+            .checked_sub(&corrected_fee)
+            .ok_or(InvalidTransaction::Custom(FEE_EXCEEDS_WITHDRAWN))?;
+
+        let tip = tip.min(corrected_fee);
+        let author = Self::block_author().filter(|author| author != &T::TreasuryModuleId::get());
+        let author_amount = match author {
+            Some(_) => T::AuthorFeeShare::get()
+                .mul_floor(corrected_fee - tip)
+                .saturating_add(tip),
+            None => T::Balance::zero(),
+        };
+
+        Self::treasury_add(asset, corrected_fee - author_amount)
+            .map_err(|_| InvalidTransaction::Payment)?;
+        if let Some(author) = author.filter(|_| !author_amount.is_zero()) {
+            Self::add(&author, asset, author_amount).map_err(|_| InvalidTransaction::Payment)?;
+            // The fee is already settled, a dust entry that can't be reaped is left as is
+            let _ = Self::reap_dust(&author, asset);
+        }
+        Self::add(who, asset, refund_amount).map_err(|_| InvalidTransaction::Payment)?;
+        let _ = Self::reap_dust(who, asset);
+        Ok(())
+    }
+
+    /// Author of the current block, found in the pre-runtime digests.
+    fn block_author() -> Option<T::AccountId> {
+        let digest = frame_system::Pallet::<T>::digest();
+        let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+        T::FindAuthor::find_author(pre_runtime_digests)
+    }
+
+    /// Part of the free balance that can be withdrawn, optionally keeping the account alive.
+    fn reducible_balance(who: &T::AccountId, asset: AssetIndex, keep_alive: bool) -> T::Balance {
+        let balance = Accounts::<T>::get(who, asset);
+        let spendable = balance.free.saturating_sub(balance.frozen);
+        if keep_alive {
+            let existential = Self::min_balance(asset).max(T::Balance::one());
+            spendable.min(balance.total().saturating_sub(existential))
+        } else {
+            spendable
+        }
+    }
+
+    /// Returns free balance left after withdrawing `amount`, respecting locks.
+    fn free_after_withdrawal(
+        balance: &AssetBalance<T::Balance>,
+        amount: T::Balance,
+    ) -> Result<T::Balance, Error<T>> {
+        let free = balance.free.checked_sub(&amount).ok_or(Error::<T>::Debt)?;
+        ensure!(free >= balance.frozen, Error::<T>::LiquidityRestrictions);
+        Ok(free)
+    }
+
+    /// Stores the locks and updates the frozen part of the free balance.
+    /// Accounts with locks hold a consumer reference.
+    fn update_locks(
+        who: &T::AccountId,
+        asset: AssetIndex,
+        locks: BoundedVec<BalanceLock<T::Balance>, T::MaxLocks>,
+    ) -> DispatchResult {
+        let frozen = locks
+            .iter()
+            .map(|lock| lock.amount)
+            .max()
+            .unwrap_or_else(T::Balance::zero);
+        let existed = Locks::<T>::contains_key(who, asset);
+        if locks.is_empty() {
+            if existed {
+                Locks::<T>::remove(who, asset);
+                frame_system::Pallet::<T>::dec_consumers(who);
+                Self::release_kept_providers(who);
+            }
+        } else {
+            if !existed {
+                frame_system::Pallet::<T>::inc_consumers(who)?;
+            }
+            Locks::<T>::insert(who, asset, locks);
+        }
+        Accounts::<T>::mutate_exists(who, asset, |maybe_balance| {
+            if let Some(balance) = maybe_balance {
+                balance.frozen = frozen;
+            }
+        });
+        Ok(())
+    }
+
+    /// Puts the whole initial issuance of a new asset to treasury.
+    fn init_issuance(asset: AssetIndex) -> DispatchResult {
+        let balance = T::InitialAssetIssuance::get();
+        Self::add(&T::TreasuryModuleId::get(), asset, balance)?;
+        TotalIssuance::<T>::insert(asset, balance);
+        CirculatingSupply::<T>::insert(asset, T::Balance::zero());
+        Ok(())
+    }
+
+    /// Creates `amount` of new supply on `who`.
+    fn issue(who: &T::AccountId, asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_existential(who, index, amount)?;
+        Self::add(who, index, amount)?;
+        TotalIssuance::<T>::try_mutate(index, |total| {
+            *total = total.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            Ok::<_, DispatchError>(())
+        })?;
+        if who != &T::TreasuryModuleId::get() {
+            CirculatingSupply::<T>::mutate(index, |supply| *supply = supply.saturating_add(amount));
+        }
+        Ok(())
+    }
+
+    /// Removes `amount` of `who` balance from the supply.
+    fn destroy(who: &T::AccountId, asset: &AssetIdOf<T>, amount: T::Balance) -> DispatchResult {
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_not_frozen(who, index)?;
+        Self::sub(who, index, amount)?;
+        TotalIssuance::<T>::mutate(index, |total| *total = total.saturating_sub(amount));
+        if who != &T::TreasuryModuleId::get() {
+            CirculatingSupply::<T>::mutate(index, |supply| *supply = supply.saturating_sub(amount));
+        }
+        Self::reap_dust(who, index)
+    }
+
+    fn treasury_add(asset: AssetIndex, amount: T::Balance) -> DispatchResult {
+        Self::add(&T::TreasuryModuleId::get(), asset, amount)?;
+        CirculatingSupply::<T>::mutate(asset, |supply| *supply = supply.saturating_sub(amount));
+        Ok(())
+    }
+
+    fn treasury_sub(asset: AssetIndex, amount: T::Balance) -> DispatchResult {
         Self::sub(&T::TreasuryModuleId::get(), asset, amount)
-            .map_err(|_| Error::<T>::EmptyTreasury.into())
+            .map_err(|_| Error::<T>::EmptyTreasury)?;
+        CirculatingSupply::<T>::mutate(asset, |supply| *supply = supply.saturating_add(amount));
+        Ok(())
+    }
+
+    fn main_index() -> Result<AssetIndex, DispatchError> {
+        T::Assets::index_of(&T::Assets::get_main())
+    }
+
+    /// Checks that tracked issuance matches the balances in `Accounts`.
+    #[cfg(feature = "try-runtime")]
+    pub fn check_issuance() -> Result<(), &'static str> {
+        use sp_std::collections::btree_map::BTreeMap;
+
+        let mut totals = BTreeMap::<AssetIndex, (T::Balance, u32)>::new();
+        for (who, asset, balance) in Accounts::<T>::iter() {
+            let (total, holders) = totals
+                .entry(asset)
+                .or_insert_with(|| (T::Balance::zero(), 0));
+            *total = total
+                .checked_add(&balance.total())
+                .ok_or("Total issuance overflow")?;
+            if !balance.total().is_zero() {
+                *holders += 1;
+                ensure!(
+                    HoldersByBalance::<T>::contains_key((
+                        asset,
+                        Self::holder_rank(balance.total()),
+                        &who
+                    )),
+                    "HoldersByBalance misses a holder"
+                );
+            }
+        }
+        ensure!(
+            HoldersByBalance::<T>::iter_keys().count() as u32
+                == totals.values().map(|(_, holders)| holders).sum::<u32>(),
+            "HoldersByBalance has stale holders"
+        );
+
+        let treasury = T::TreasuryModuleId::get();
+        for (asset, issuance) in TotalIssuance::<T>::iter() {
+            let (total, holders) = totals
+                .remove(&asset)
+                .unwrap_or_else(|| (T::Balance::zero(), 0));
+            ensure!(issuance == total, "TotalIssuance doesn't match Accounts");
+            ensure!(
+                HolderCount::<T>::get(asset) == holders,
+                "HolderCount doesn't match Accounts"
+            );
+            let circulating = total.saturating_sub(Accounts::<T>::get(&treasury, asset).total());
+            ensure!(
+                CirculatingSupply::<T>::get(asset) == circulating,
+                "CirculatingSupply doesn't match Accounts"
+            );
+        }
+        ensure!(
+            totals.values().all(|(total, _)| total.is_zero()),
+            "Accounts hold an asset without TotalIssuance"
+        );
+        Ok(())
+    }
+
+    /// Part of the `asset` supply held outside of treasury, zero for unknown assets.
+    pub fn circulating_supply(asset: &AssetIdOf<T>) -> T::Balance {
+        T::Assets::index_of(asset)
+            .map(CirculatingSupply::<T>::get)
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
+    /// Number of accounts holding a non-zero balance of `asset`, zero for unknown assets.
+    pub fn holder_count(asset: &AssetIdOf<T>) -> u32 {
+        T::Assets::index_of(asset)
+            .map(HolderCount::<T>::get)
+            .unwrap_or(0)
+    }
+
+    /// Up to `limit` largest holders of `asset` with their total balances, empty for
+    /// unknown assets.
+    pub fn top_holders(asset: &AssetIdOf<T>, limit: u32) -> Vec<(T::AccountId, T::Balance)> {
+        let asset = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => return Vec::new(),
+        };
+        HoldersByBalance::<T>::iter_key_prefix((asset,))
+            .take(limit as usize)
+            .map(|(rank, who)| {
+                let total = u128::MAX - u128::from_be_bytes(rank);
+                (who, T::Balance::unique_saturated_from(total))
+            })
+            .collect()
+    }
+
+    /// Walks `iter` from its raw start key, taking at most `limit` entries
+    /// while `page` maps them to balances.
+    fn balances_page<I, E, F>(
+        mut iter: PrefixIterator<E>,
+        limit: u32,
+        mut page: F,
+    ) -> BalancesPage<T::AccountId, AssetIdOf<T>, T::Balance>
+    where
+        F: FnMut(E) -> Option<I>,
+        I: IntoIterator<Item = (T::AccountId, AssetIdOf<T>, T::Balance)>,
+    {
+        let mut balances = Vec::new();
+        // No cursor to resume from, a zero limit would return the same cursor forever
+        if limit == 0 {
+            return BalancesPage {
+                balances,
+                cursor: None,
+            };
+        }
+        for _ in 0..limit {
+            match iter.next().and_then(&mut page) {
+                Some(entries) => balances.extend(entries),
+                None => {
+                    return BalancesPage {
+                        balances,
+                        cursor: None,
+                    }
+                }
+            }
+        }
+        BalancesPage {
+            balances,
+            cursor: Some(iter.last_raw_key().to_vec()),
+        }
     }
 }
 
@@ -204,47 +1017,264 @@ impl<T: Config> Currency for Pallet<T> {
     type AssetId = AssetIdOf<T>;
     type CurrencyError = DispatchError;
 
+    #[frame_support::transactional]
     fn mint(
         who: &T::AccountId,
         asset: &AssetIdOf<T>,
         amount: T::Balance,
     ) -> Result<(), DispatchError> {
-        T::Assets::check(asset)?;
-        Self::treasury_sub(asset, amount)?;
-        Self::add(who, asset, amount)?;
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_existential(who, index, amount)?;
+        Self::treasury_sub(index, amount)?;
+        Self::add(who, index, amount)?;
         Ok(())
     }
 
+    #[frame_support::transactional]
     fn burn(
         who: &T::AccountId,
         asset: &AssetIdOf<T>,
         amount: T::Balance,
     ) -> Result<(), DispatchError> {
-        T::Assets::check(asset)?;
-        Self::sub(who, asset, amount)?;
-        Self::treasury_add(asset, amount)?;
-        Ok(())
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_not_frozen(who, index)?;
+        Self::sub(who, index, amount)?;
+        Self::treasury_add(index, amount)?;
+        Self::reap_dust(who, index)
     }
 
+    #[frame_support::transactional]
     fn transfer(
         from: &T::AccountId,
         to: &T::AccountId,
         asset: &AssetIdOf<T>,
         amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_not_frozen(from, index)?;
+        Self::ensure_existential(to, index, amount)?;
+        Self::sub(from, index, amount)?;
+        Self::add(to, index, amount)?;
+        Self::reap_dust(from, index)
+    }
+
+    fn balance(who: &T::AccountId, asset: &AssetIdOf<T>) -> T::Balance {
+        T::Assets::index_of(asset)
+            .map(|index| Accounts::<T>::get(who, index).free)
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
+    fn reserved_balance(who: &T::AccountId, asset: &AssetIdOf<T>) -> T::Balance {
+        T::Assets::index_of(asset)
+            .map(|index| Accounts::<T>::get(who, index).reserved)
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
+    fn reserve(
+        who: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::mutate_account(who, index, |balance| {
+            let free = Self::free_after_withdrawal(balance, amount)?;
+            balance.reserved = balance
+                .reserved
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            balance.free = free;
+            Ok(())
+        })
+    }
+
+    fn unreserve(
+        who: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::mutate_account(who, index, |balance| {
+            balance.reserved = balance
+                .reserved
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientReserved)?;
+            balance.free = balance
+                .free
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            Ok(())
+        })
+    }
+
+    #[frame_support::transactional]
+    fn repatriate_reserved(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
     ) -> Result<(), DispatchError> {
         T::Assets::check(asset)?;
-        Self::sub(from, asset, amount)?;
-        Self::add(to, asset, amount)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::ensure_not_frozen(from, index)?;
+        Self::ensure_existential(to, index, amount)?;
+        Self::mutate_account(from, index, |balance| {
+            balance.reserved = balance
+                .reserved
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientReserved)?;
+            Ok(())
+        })?;
+        Self::add(to, index, amount)?;
+        Self::mutate_account(to, index, |balance| {
+            balance.free -= amount;
+            balance.reserved = balance
+                .reserved
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            Ok(())
+        })?;
+        Self::reap_dust(from, index)
+    }
+
+    #[frame_support::transactional]
+    fn slash_reserved(
+        who: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check_active(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        Self::mutate_account(who, index, |balance| {
+            balance.reserved = balance
+                .reserved
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientReserved)?;
+            Ok(())
+        })?;
+        Self::treasury_add(index, amount)?;
+        Self::reap_dust(who, index)
+    }
+
+    fn set_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        ensure!(
+            Accounts::<T>::contains_key(who, index),
+            Error::<T>::NoAccount
+        );
+        let mut locks = Locks::<T>::get(who, index);
+        match locks.iter_mut().find(|lock| lock.id == id) {
+            Some(lock) => lock.amount = amount,
+            None => locks
+                .try_push(BalanceLock { id, amount })
+                .map_err(|_| Error::<T>::TooManyLocks)?,
+        }
+        Self::update_locks(who, index, locks)
+    }
+
+    fn remove_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        asset: &AssetIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let index = T::Assets::index_of(asset)?;
+        let mut locks = Locks::<T>::get(who, index);
+        locks.retain(|lock| lock.id != id);
+        Self::update_locks(who, index, locks)
+    }
+
+    fn allowance(owner: &T::AccountId, spender: &T::AccountId, asset: &AssetIdOf<T>) -> T::Balance {
+        T::Assets::index_of(asset)
+            .map(|index| Approvals::<T>::get((owner, index, spender)))
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
+    fn approve(
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        T::Assets::check(asset)?;
+        let index = T::Assets::index_of(asset)?;
+        ensure!(
+            Accounts::<T>::contains_key(owner, index),
+            Error::<T>::NoAccount
+        );
+        if amount.is_zero() {
+            Approvals::<T>::remove((owner, index, spender));
+        } else {
+            Approvals::<T>::insert((owner, index, spender), amount);
+        }
         Ok(())
     }
 
-    fn balance(who: &T::AccountId, asset: &AssetIdOf<T>) -> T::Balance {
-        Accounts::<T>::get(who, asset)
+    #[frame_support::transactional]
+    fn transfer_from(
+        spender: &T::AccountId,
+        owner: &T::AccountId,
+        to: &T::AccountId,
+        asset: &AssetIdOf<T>,
+        amount: T::Balance,
+    ) -> Result<(), DispatchError> {
+        let index = T::Assets::index_of(asset)?;
+        let approved = Approvals::<T>::get((owner, index, spender));
+        let remaining = approved
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::Unapproved)?;
+        Self::transfer(owner, to, asset, amount)?;
+        // The owner entry may have been reaped along with its approvals
+        if !remaining.is_zero() && Accounts::<T>::contains_key(owner, index) {
+            Approvals::<T>::insert((owner, index, spender), remaining);
+        } else {
+            Approvals::<T>::remove((owner, index, spender));
+        }
+        Ok(())
     }
 
+    fn cancel_approval(
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        asset: &AssetIdOf<T>,
+    ) -> Result<(), DispatchError> {
+        let index = T::Assets::index_of(asset)?;
+        ensure!(
+            Approvals::<T>::contains_key((owner, index, spender)),
+            Error::<T>::Unapproved
+        );
+        Approvals::<T>::remove((owner, index, spender));
+        Ok(())
+    }
+
+    fn is_frozen(who: &T::AccountId, asset: &AssetIdOf<T>) -> bool {
+        T::Assets::index_of(asset)
+            .map(|index| FrozenAccounts::<T>::contains_key(index, who))
+            .unwrap_or(false)
+    }
+
+    fn total_issuance(asset: &AssetIdOf<T>) -> T::Balance {
+        T::Assets::index_of(asset)
+            .map(TotalIssuance::<T>::get)
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
+    /// An account has one entry per registered asset at most, so its prefix is bounded
+    /// by the assets limit.
     fn total_in_stable(who: &Self::AccountId) -> Self::Balance {
-        Accounts::<T>::iter_prefix(who).fold(T::Balance::zero(), |acc, (asset, balance)| {
-            if let Ok(amount) = T::Prices::to_stable_amount(&asset, balance) {
+        Accounts::<T>::iter_prefix(who).fold(T::Balance::zero(), |acc, (index, balance)| {
+            if let Ok(amount) = T::Assets::id_of(index)
+                .and_then(|asset| T::Prices::to_stable_amount(&asset, balance.free))
+            {
                 acc + amount
             } else {
                 acc
@@ -252,11 +1282,58 @@ impl<T: Config> Currency for Pallet<T> {
         })
     }
 
-    type BalancesIter =
-        frame_support::storage::PrefixIterator<(T::AccountId, AssetIdOf<T>, T::Balance)>;
+    fn account_balances(
+        who: &T::AccountId,
+        cursor: Option<Vec<u8>>,
+        limit: u32,
+    ) -> BalancesPage<T::AccountId, AssetIdOf<T>, T::Balance> {
+        let start = cursor.unwrap_or_else(|| {
+            let mut prefix = Accounts::<T>::final_prefix().to_vec();
+            prefix.extend(who.using_encoded(Blake2_128Concat::hash));
+            prefix
+        });
+        Self::balances_page(
+            Accounts::<T>::iter_from(start),
+            limit,
+            |(owner, index, balance)| {
+                (&owner == who).then(|| {
+                    T::Assets::id_of(index)
+                        .ok()
+                        .map(|asset| (owner, asset, balance.free))
+                })
+            },
+        )
+    }
 
-    fn iter_balances() -> Self::BalancesIter {
-        Accounts::<T>::iter()
+    fn asset_balances(
+        asset: &AssetIdOf<T>,
+        cursor: Option<Vec<u8>>,
+        limit: u32,
+    ) -> BalancesPage<T::AccountId, AssetIdOf<T>, T::Balance> {
+        let index = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => {
+                return BalancesPage {
+                    balances: Vec::new(),
+                    cursor: None,
+                }
+            }
+        };
+        let start = cursor.unwrap_or_else(|| {
+            let mut prefix = AssetAccounts::<T>::final_prefix().to_vec();
+            prefix.extend(index.using_encoded(Twox64Concat::hash));
+            prefix
+        });
+        Self::balances_page(
+            AssetAccounts::<T>::iter_from(start),
+            limit,
+            |(entry_asset, who, ())| {
+                (entry_asset == index).then(|| {
+                    let free = Accounts::<T>::get(&who, index).free;
+                    Some((who, asset.clone(), free))
+                })
+            },
+        )
     }
 }
 
@@ -277,7 +1354,9 @@ impl<T: Config + pallet_transaction_payment::Config>
             return Ok(None);
         }
 
-        Self::sub(who, &T::Assets::get_main(), fee).map_err(|_| InvalidTransaction::Payment)?;
+        let main = Self::main_index().map_err(|_| InvalidTransaction::Payment)?;
+        Self::ensure_not_frozen(who, main).map_err(|_| InvalidTransaction::Payment)?;
+        Self::sub(who, main, fee).map_err(|_| InvalidTransaction::Payment)?;
 
         Ok(Some(fee))
     }
@@ -291,11 +1370,8 @@ impl<T: Config + pallet_transaction_payment::Config>
         already_withdrawn: Self::LiquidityInfo,
     ) -> Result<(), frame_support::unsigned::TransactionValidityError> {
         if let Some(paid) = already_withdrawn {
-            let refund_amount = paid - corrected_fee;
-            Self::treasury_add(&T::Assets::get_main(), corrected_fee)
-                .map_err(|_| InvalidTransaction::Payment)?;
-            Self::add(who, &T::Assets::get_main(), refund_amount)
-                .map_err(|_| InvalidTransaction::Payment)?;
+            let main = Self::main_index().map_err(|_| InvalidTransaction::Payment)?;
+            Self::deposit_fee(who, main, paid, corrected_fee, tip)?;
         }
 
         Self::deposit_event(Event::TransactionPayment {
@@ -309,9 +1385,56 @@ impl<T: Config + pallet_transaction_payment::Config>
 
 impl<T: Config> OnAssetCreate<AssetIdOf<T>> for Pallet<T> {
     fn on_asset_create(asset: &AssetIdOf<T>) -> Option<()> {
+        let index = T::Assets::index_of(asset).ok()?;
+        Self::init_issuance(index).ok()
+    }
+}
+
+impl<T: Config> OnAssetRemove<AssetIdOf<T>> for Pallet<T> {
+    /// Sweeps all balances of the removed asset to treasury and drops their locks.
+    /// Swept accounts leave `AssetAccounts`, so every call starts from the prefix head.
+    fn on_asset_remove(asset: &AssetIdOf<T>, limit: u32) -> (u32, bool, Weight) {
+        let db = T::DbWeight::get();
+        let asset = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => return (0, true, db.reads(1)),
+        };
+
         let treasury = T::TreasuryModuleId::get();
-        let balance = T::InitialAssetIssuance::get();
-        Accounts::<T>::insert(&treasury, asset, balance);
-        Some(())
+        let mut scanned = 0u64;
+        let holders: Vec<_> = AssetAccounts::<T>::iter_key_prefix(asset)
+            .inspect(|_| scanned += 1)
+            .filter(|who| who != &treasury)
+            .take(limit as usize + 1)
+            .collect();
+        let done = holders.len() <= limit as usize;
+        let mut count = 0;
+        for who in holders.into_iter().take(limit as usize) {
+            let total = Accounts::<T>::get(&who, asset).total();
+            Self::purge_account(&who, asset);
+            let _ = Self::treasury_add(asset, total);
+            count += 1;
+        }
+        // Per holder: the entry, its locks, approvals, system account, holder count and
+        // treasury entry.
+        let weight = db
+            .reads(scanned.saturating_add(2))
+            .saturating_add(db.reads_writes(7, 8).saturating_mul(count as Weight));
+        (count, done, weight)
+    }
+
+    fn on_asset_removed(asset: &AssetIdOf<T>) -> Weight {
+        let db = T::DbWeight::get();
+        let asset = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => return db.reads(1),
+        };
+        Self::purge_account(&T::TreasuryModuleId::get(), asset);
+        TotalIssuance::<T>::remove(asset);
+        CirculatingSupply::<T>::remove(asset);
+        HolderCount::<T>::remove(asset);
+        let frozen = FrozenAccounts::<T>::drain_prefix(asset).count() as Weight;
+        db.reads_writes(8, 11)
+            .saturating_add(db.reads_writes(frozen, frozen))
     }
 }
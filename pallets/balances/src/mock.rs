@@ -0,0 +1,225 @@
+use crate as pallet_balances;
+use frame_support::{
+    parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, ConstU8, Everything, FindAuthor, GenesisBuild},
+    weights::IdentityFee,
+};
+use primitives::{
+    assets::{self, AssetGetter, AssetId, AssetTeam},
+    currency::OnBalanceChanged,
+    prices::{PriceGetter, Rounding},
+    testing,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    ConsensusEngineId, DispatchError, Perbill,
+};
+use std::cell::RefCell;
+
+pub use primitives::testing::btc;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const AUTHOR: AccountId = 10;
+pub const TREASURY: AccountId = 100;
+pub const INITIAL_BALANCE: u128 = 1_000_000_000_000;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_transaction_payment::Config for Test {
+    type OnChargeTransaction = Balances;
+    type OperationalFeeMultiplier = ConstU8<5>;
+    type WeightToFee = IdentityFee<u128>;
+    type LengthToFee = IdentityFee<u128>;
+    type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+    /// Main asset and `btc`, indexed in this order.
+    pub TestAssetList: Vec<AssetId> = vec![assets::tok(), btc()];
+}
+
+pub type TestAssets = testing::TestAssets<TestAssetList>;
+
+/// No asset has a team, balances are managed by root only.
+pub struct TestTeam;
+
+impl AssetTeam<AccountId, AssetId> for TestTeam {
+    fn is_owner(_who: &AccountId, _asset: &AssetId) -> bool {
+        false
+    }
+
+    fn is_issuer(_who: &AccountId, _asset: &AssetId) -> bool {
+        false
+    }
+
+    fn is_freezer(_who: &AccountId, _asset: &AssetId) -> bool {
+        false
+    }
+
+    fn is_owned(_asset: &AssetId) -> bool {
+        false
+    }
+}
+
+/// Whole-unit prices: `btc` is worth 2 of the main asset.
+pub struct TestPrices;
+
+impl TestPrices {
+    fn price(id: &AssetId) -> Result<u128, DispatchError> {
+        match TestAssets::index_of(id)? {
+            0 => Ok(1),
+            _ => Ok(2),
+        }
+    }
+}
+
+impl PriceGetter for TestPrices {
+    type AssetId = AssetId;
+    type Balance = u128;
+    type Price = u128;
+    type PriceError = DispatchError;
+
+    fn get(id: &AssetId) -> Result<u128, DispatchError> {
+        Self::price(id)
+    }
+
+    fn base_asset() -> AssetId {
+        assets::tok()
+    }
+
+    fn exchange_with_rounding(
+        from: &AssetId,
+        to: &AssetId,
+        amount: u128,
+        rounding: Rounding,
+    ) -> Result<u128, DispatchError> {
+        let value = amount
+            .checked_mul(Self::price(from)?)
+            .ok_or(DispatchError::Other("Overflow"))?;
+        let price_to = Self::price(to)?;
+        Ok(match rounding {
+            Rounding::Down => value / price_to,
+            Rounding::Up => (value + price_to - 1) / price_to,
+            Rounding::Nearest => (value + price_to / 2) / price_to,
+        })
+    }
+}
+
+parameter_types! {
+    pub const TreasuryModuleId: AccountId = TREASURY;
+    pub const AuthorFeeShare: Perbill = Perbill::from_percent(20);
+}
+
+thread_local! {
+    static BLOCK_AUTHOR: RefCell<Option<AccountId>> = RefCell::new(Some(AUTHOR));
+    static BALANCE_CHANGES: RefCell<Vec<(AccountId, AssetId, u128, u128)>> = RefCell::new(Vec::new());
+}
+
+/// Balance changes reported since the last call.
+pub fn take_balance_changes() -> Vec<(AccountId, AssetId, u128, u128)> {
+    BALANCE_CHANGES.with(|changes| changes.take())
+}
+
+/// Records every reported balance change.
+pub struct RecordChanges;
+
+impl OnBalanceChanged<AccountId, AssetId, u128> for RecordChanges {
+    fn on_balance_changed(who: &AccountId, asset: &AssetId, old: &u128, new: &u128) {
+        BALANCE_CHANGES
+            .with(|changes| changes.borrow_mut().push((*who, asset.clone(), *old, *new)));
+    }
+}
+
+pub fn set_author(author: Option<AccountId>) {
+    BLOCK_AUTHOR.with(|a| *a.borrow_mut() = author);
+}
+
+/// Block author set by the test with `set_author`, digests are ignored.
+pub struct TestAuthor;
+
+impl FindAuthor<AccountId> for TestAuthor {
+    fn find_author<'a, I>(_digests: I) -> Option<AccountId>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        BLOCK_AUTHOR.with(|a| *a.borrow())
+    }
+}
+
+impl pallet_balances::Config for Test {
+    type Event = Event;
+    type Balance = u128;
+    type Assets = TestAssets;
+    type AssetTeam = TestTeam;
+    type Prices = TestPrices;
+    type TreasuryModuleId = TreasuryModuleId;
+    type InitialAssetIssuance = ConstU128<1_000_000_000_000_000>;
+    type MaxLocks = ConstU32<4>;
+    type MaxBatchTransfers = ConstU32<4>;
+    type FindAuthor = TestAuthor;
+    type AuthorFeeShare = AuthorFeeShare;
+    type OnBalanceChanged = RecordChanges;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        treasury: vec![assets::tok(), btc()],
+        balances: vec![(
+            ALICE,
+            vec![(assets::tok(), INITIAL_BALANCE), (btc(), INITIAL_BALANCE)],
+        )],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
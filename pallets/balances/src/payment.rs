@@ -0,0 +1,189 @@
+//! Signed extension charging transaction fees in an asset chosen by the sender.
+
+use crate::{AssetIdOf, Config, Event, Pallet};
+use codec::{Decode, Encode, HasCompact};
+use frame_support::weights::{DispatchInfo, PostDispatchInfo};
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+use primitives::{
+    assets::{AssetGetter, AssetIndex},
+    prices::{PriceGetter, Rounding},
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension, Zero},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+    DispatchResult,
+};
+use sp_std::fmt;
+
+/// Replaces `ChargeTransactionPayment`. The fee is paid in the main asset unless `asset_id`
+/// is set, then it's converted with oracle prices and credited to treasury in that asset.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ChargeAssetTxPayment<T: Config> {
+    #[codec(compact)]
+    tip: T::Balance,
+    asset_id: Option<AssetIdOf<T>>,
+}
+
+/// Fee withdrawn before dispatch, settled in `post_dispatch`.
+pub enum InitialPayment<T: Config> {
+    Nothing,
+    Main(Option<T::Balance>),
+    Asset(AssetIndex, AssetIdOf<T>, T::Balance),
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> {
+    pub fn from(tip: T::Balance, asset_id: Option<AssetIdOf<T>>) -> Self {
+        Self { tip, asset_id }
+    }
+}
+
+impl<T> ChargeAssetTxPayment<T>
+where
+    T: Config + pallet_transaction_payment::Config<OnChargeTransaction = Pallet<T>>,
+    T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+    fn withdraw_fee(
+        &self,
+        who: &T::AccountId,
+        call: &T::Call,
+        info: &DispatchInfoOf<T::Call>,
+        len: usize,
+    ) -> Result<(T::Balance, InitialPayment<T>), TransactionValidityError> {
+        let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, self.tip);
+        if fee.is_zero() {
+            return Ok((fee, InitialPayment::Nothing));
+        }
+
+        match &self.asset_id {
+            Some(asset) if asset != &T::Assets::get_main() => {
+                T::Assets::check_active(asset).map_err(|_| InvalidTransaction::Payment)?;
+                let index = T::Assets::index_of(asset).map_err(|_| InvalidTransaction::Payment)?;
+                Pallet::<T>::ensure_not_frozen(who, index)
+                    .map_err(|_| InvalidTransaction::Payment)?;
+                let amount = T::Prices::exchange_with_rounding(
+                    &T::Assets::get_main(),
+                    asset,
+                    fee,
+                    Rounding::Up,
+                )
+                .map_err(|_| InvalidTransaction::Payment)?;
+                Pallet::<T>::sub(who, index, amount).map_err(|_| InvalidTransaction::Payment)?;
+                Ok((fee, InitialPayment::Asset(index, asset.clone(), amount)))
+            }
+            _ => {
+                <Pallet<T> as OnChargeTransaction<T>>::withdraw_fee(who, call, info, fee, self.tip)
+                    .map(|paid| (fee, InitialPayment::Main(paid)))
+            }
+        }
+    }
+}
+
+impl<T: Config> fmt::Debug for ChargeAssetTxPayment<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ChargeAssetTxPayment<{:?}, {:?}>",
+            self.tip, self.asset_id
+        )
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T> SignedExtension for ChargeAssetTxPayment<T>
+where
+    T: Config + pallet_transaction_payment::Config<OnChargeTransaction = Pallet<T>>,
+    T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+    T::Balance: HasCompact + Send + Sync,
+{
+    const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+    type AccountId = T::AccountId;
+    type Call = T::Call;
+    type AdditionalSigned = ();
+    type Pre = (T::Balance, T::AccountId, InitialPayment<T>);
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &T::AccountId,
+        call: &T::Call,
+        info: &DispatchInfoOf<T::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        let (fee, _) = self.withdraw_fee(who, call, info, len)?;
+        let priority = ChargeTransactionPayment::<T>::get_priority(info, len, self.tip, fee);
+        Ok(ValidTransaction {
+            priority,
+            ..Default::default()
+        })
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &T::AccountId,
+        call: &T::Call,
+        info: &DispatchInfoOf<T::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        let (_, initial_payment) = self.withdraw_fee(who, call, info, len)?;
+        Ok((self.tip, who.clone(), initial_payment))
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        info: &DispatchInfoOf<T::Call>,
+        post_info: &PostDispatchInfoOf<T::Call>,
+        len: usize,
+        _result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        let (tip, who, initial_payment) = match pre {
+            Some(pre) => pre,
+            None => return Ok(()),
+        };
+        let actual_fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+            len as u32, info, post_info, tip,
+        );
+
+        match initial_payment {
+            InitialPayment::Nothing => Ok(()),
+            InitialPayment::Main(already_withdrawn) => {
+                <Pallet<T> as OnChargeTransaction<T>>::correct_and_deposit_fee(
+                    &who,
+                    info,
+                    post_info,
+                    actual_fee,
+                    tip,
+                    already_withdrawn,
+                )
+            }
+            InitialPayment::Asset(index, asset, paid) => {
+                // Prices may have changed since withdrawal, never charge more than paid
+                let main = T::Assets::get_main();
+                let fee =
+                    T::Prices::exchange_with_rounding(&main, &asset, actual_fee, Rounding::Up)
+                        .map_or(paid, |fee| fee.min(paid));
+                let tip = T::Prices::exchange_with_rounding(&main, &asset, tip, Rounding::Down)
+                    .map_or(Zero::zero(), |tip| tip.min(fee));
+                Pallet::<T>::deposit_fee(&who, index, paid, fee, tip)?;
+                Pallet::<T>::deposit_event(Event::AssetTransactionPayment {
+                    who,
+                    asset,
+                    fee,
+                    tip,
+                });
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Runtime API backing the `balances_*` RPC methods and balance analytics.
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait BalancesApi<AccountId, AssetId, Balance>
+    where
+        AccountId: Codec,
+        AssetId: Codec,
+        Balance: Codec,
+    {
+        /// Sum of all balances of `asset`, zero for unknown assets.
+        fn total_issuance(asset: AssetId) -> Balance;
+
+        /// Part of the `asset` supply held outside of treasury, zero for unknown assets.
+        fn circulating_supply(asset: AssetId) -> Balance;
+
+        /// Number of accounts holding a non-zero balance of `asset`.
+        fn holder_count(asset: AssetId) -> u32;
+
+        /// Up to `limit` largest holders of `asset` with their total balances.
+        fn top_holders(asset: AssetId, limit: u32) -> Vec<(AccountId, Balance)>;
+    }
+}
@@ -0,0 +1,532 @@
+use crate::{
+    mock::*, payment::ChargeAssetTxPayment, Accounts, Error, Event as BalancesEvent, HolderCount,
+    FEE_EXCEEDS_WITHDRAWN,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    weights::{DispatchInfo, Pays, PostDispatchInfo},
+};
+use pallet_transaction_payment::OnChargeTransaction;
+use primitives::{assets, currency::Currency};
+use sp_runtime::{
+    traits::SignedExtension,
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+    DispatchError,
+};
+
+fn balance(who: AccountId, asset: &assets::AssetId) -> u128 {
+    <Balances as Currency>::balance(&who, asset)
+}
+
+fn remark() -> Call {
+    Call::System(frame_system::Call::remark { remark: vec![] })
+}
+
+/// Withdraws `paid` of the main asset from `ALICE` and settles `fee` with `tip` out of it.
+fn pay_main(paid: u128, fee: u128, tip: u128) -> Result<(), TransactionValidityError> {
+    let info = DispatchInfo::default();
+    let withdrawn =
+        <Balances as OnChargeTransaction<Test>>::withdraw_fee(&ALICE, &remark(), &info, paid, tip)?;
+    assert_eq!(withdrawn, Some(paid));
+    <Balances as OnChargeTransaction<Test>>::correct_and_deposit_fee(
+        &ALICE,
+        &info,
+        &PostDispatchInfo::default(),
+        fee,
+        tip,
+        withdrawn,
+    )
+}
+
+#[test]
+fn unused_fee_is_refunded() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        let treasury = balance(TREASURY, &main);
+
+        assert_eq!(pay_main(100, 60, 10), Ok(()));
+
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE - 60);
+        // 20% of the fee without tip, plus the tip
+        assert_eq!(balance(AUTHOR, &main), 10 + 10);
+        assert_eq!(balance(TREASURY, &main), treasury + 40);
+        System::assert_last_event(Event::Balances(BalancesEvent::TransactionPayment {
+            who: ALICE,
+            fee: 60,
+            tip: 10,
+        }));
+    });
+}
+
+#[test]
+fn fee_exceeding_withdrawn_is_rejected() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            pay_main(50, 60, 0),
+            Err(TransactionValidityError::Invalid(
+                InvalidTransaction::Custom(FEE_EXCEEDS_WITHDRAWN)
+            ))
+        );
+    });
+}
+
+#[test]
+fn tip_is_credited_to_block_author() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        let treasury = balance(TREASURY, &main);
+
+        assert_eq!(pay_main(100, 30, 30), Ok(()));
+
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE - 30);
+        assert_eq!(balance(AUTHOR, &main), 30);
+        assert_eq!(balance(TREASURY, &main), treasury);
+    });
+}
+
+#[test]
+fn fee_goes_to_treasury_without_author() {
+    set_author(None);
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        let treasury = balance(TREASURY, &main);
+
+        assert_eq!(pay_main(100, 60, 10), Ok(()));
+
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE - 60);
+        assert_eq!(balance(AUTHOR, &main), 0);
+        assert_eq!(balance(TREASURY, &main), treasury + 60);
+    });
+}
+
+#[test]
+fn fee_is_paid_in_chosen_asset() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        let treasury = balance(TREASURY, &btc());
+        let info = DispatchInfo {
+            weight: 1_000,
+            ..Default::default()
+        };
+        let post_info = PostDispatchInfo {
+            actual_weight: Some(0),
+            pays_fee: Pays::Yes,
+        };
+        let (len, tip) = (10, 5);
+
+        let fee = TransactionPayment::compute_fee(len, &info, tip);
+        let pre = ChargeAssetTxPayment::<Test>::from(tip, Some(btc()))
+            .pre_dispatch(&ALICE, &remark(), &info, len as usize)
+            .unwrap();
+        // Rounded up in favour of treasury, nothing is taken from the main asset
+        let paid = (fee + 1) / 2;
+        assert_eq!(balance(ALICE, &btc()), INITIAL_BALANCE - paid);
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE);
+
+        assert_eq!(
+            ChargeAssetTxPayment::<Test>::post_dispatch(
+                Some(pre),
+                &info,
+                &post_info,
+                len as usize,
+                &Ok(())
+            ),
+            Ok(())
+        );
+
+        // The unused weight fee is refunded, the tip is rounded down
+        let actual_fee = TransactionPayment::compute_actual_fee(len, &info, &post_info, tip);
+        assert_eq!(actual_fee, fee - 1_000);
+        let (btc_fee, btc_tip) = ((actual_fee + 1) / 2, tip / 2);
+        let author = (btc_fee - btc_tip) / 5 + btc_tip;
+        assert_eq!(balance(ALICE, &btc()), INITIAL_BALANCE - btc_fee);
+        assert_eq!(balance(AUTHOR, &btc()), author);
+        assert_eq!(balance(TREASURY, &btc()), treasury + btc_fee - author);
+        System::assert_last_event(Event::Balances(BalancesEvent::AssetTransactionPayment {
+            who: ALICE,
+            asset: btc(),
+            fee: btc_fee,
+            tip: btc_tip,
+        }));
+    });
+}
+
+#[test]
+fn frozen_accounts_cannot_pay_fees() {
+    new_test_ext().execute_with(|| {
+        let info = DispatchInfo::default();
+        assert_ok!(Balances::freeze(Origin::root(), ALICE, assets::tok()));
+        assert_eq!(
+            pay_main(100, 60, 0),
+            Err(InvalidTransaction::Payment.into())
+        );
+
+        assert_ok!(Balances::freeze(Origin::root(), ALICE, btc()));
+        assert_eq!(
+            ChargeAssetTxPayment::<Test>::from(0, Some(btc()))
+                .pre_dispatch(&ALICE, &remark(), &info, 10)
+                .map(|_| ()),
+            Err(InvalidTransaction::Payment.into())
+        );
+        assert_eq!(balance(ALICE, &assets::tok()), INITIAL_BALANCE);
+        assert_eq!(balance(ALICE, &btc()), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn balance_changes_report_totals_after_mutation() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        assert_ok!(<Balances as Currency>::reserve(&ALICE, &main, 300));
+        take_balance_changes();
+
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            AUTHOR,
+            main.clone(),
+            100
+        ));
+        assert_eq!(
+            take_balance_changes(),
+            vec![
+                (ALICE, main.clone(), INITIAL_BALANCE, INITIAL_BALANCE - 100),
+                (AUTHOR, main, 0, 100),
+            ]
+        );
+    });
+}
+
+#[test]
+fn asset_balances_page_through_holders_of_asset() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            AUTHOR,
+            btc(),
+            100
+        ));
+
+        let mut holders = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = <Balances as Currency>::asset_balances(&btc(), cursor, 1);
+            holders.extend(page.balances);
+            cursor = page.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        holders.sort_by_key(|(who, _, _)| *who);
+
+        let treasury = balance(TREASURY, &btc());
+        assert_eq!(
+            holders,
+            vec![
+                (ALICE, btc(), INITIAL_BALANCE - 100),
+                (AUTHOR, btc(), 100),
+                (TREASURY, btc(), treasury),
+            ]
+        );
+
+        let page = <Balances as Currency>::asset_balances(&btc(), None, 0);
+        assert!(page.balances.is_empty());
+        assert_eq!(page.cursor, None);
+    });
+}
+
+#[test]
+fn top_holders_are_ordered_by_total_balance() {
+    new_test_ext().execute_with(|| {
+        let treasury = balance(TREASURY, &btc());
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            AUTHOR,
+            btc(),
+            300
+        ));
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), 2, btc(), 200));
+        // Reserved balance counts towards the total
+        assert_ok!(<Balances as Currency>::reserve(&2, &btc(), 150));
+        assert_eq!(HolderCount::<Test>::get(1), 4);
+
+        assert_eq!(
+            Balances::top_holders(&btc(), 4),
+            vec![
+                (TREASURY, treasury),
+                (ALICE, INITIAL_BALANCE - 500),
+                (AUTHOR, 300),
+                (2, 200),
+            ]
+        );
+
+        assert_ok!(Balances::transfer(Origin::signed(AUTHOR), 2, btc(), 300));
+        assert_eq!(
+            Balances::top_holders(&btc(), 3),
+            vec![
+                (TREASURY, treasury),
+                (ALICE, INITIAL_BALANCE - 500),
+                (2, 500),
+            ]
+        );
+        assert_eq!(HolderCount::<Test>::get(1), 3);
+    });
+}
+
+#[test]
+fn mint_and_burn_change_total_issuance() {
+    new_test_ext().execute_with(|| {
+        let issuance = <Balances as Currency>::total_issuance(&btc());
+        let treasury = balance(TREASURY, &btc());
+
+        assert_noop!(
+            Balances::mint(Origin::signed(ALICE), ALICE, btc(), 1_000),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(Balances::mint(Origin::root(), BOB, btc(), 1_000));
+        System::assert_last_event(Event::Balances(BalancesEvent::Minted {
+            who: BOB,
+            asset: btc(),
+            amount: 1_000,
+        }));
+        assert_ok!(Balances::burn(Origin::root(), BOB, btc(), 400));
+        assert_eq!(balance(BOB, &btc()), 600);
+        assert_eq!(
+            <Balances as Currency>::total_issuance(&btc()),
+            issuance + 600
+        );
+
+        // Currency moves treasury funds and leaves the supply as is
+        assert_ok!(<Balances as Currency>::mint(&BOB, &btc(), 100));
+        assert_ok!(<Balances as Currency>::burn(&ALICE, &btc(), 50));
+        assert_eq!(balance(TREASURY, &btc()), treasury - 50);
+        assert_eq!(
+            <Balances as Currency>::total_issuance(&btc()),
+            issuance + 600
+        );
+    });
+}
+
+#[test]
+fn reserves_and_locks_restrict_spending() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        assert_ok!(<Balances as Currency>::reserve(&ALICE, &main, 100));
+        assert_eq!(<Balances as Currency>::reserved_balance(&ALICE, &main), 100);
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE - 100);
+
+        // The lock leaves 100 of the free balance spendable
+        assert_ok!(<Balances as Currency>::set_lock(
+            *b"testlock",
+            &ALICE,
+            &main,
+            INITIAL_BALANCE - 200
+        ));
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, main.clone(), 101),
+            Error::<Test>::LiquidityRestrictions
+        );
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            main.clone(),
+            100
+        ));
+        assert_noop!(
+            <Balances as Currency>::reserve(&ALICE, &main, 1),
+            Error::<Test>::LiquidityRestrictions
+        );
+
+        assert_ok!(<Balances as Currency>::remove_lock(
+            *b"testlock",
+            &ALICE,
+            &main
+        ));
+        assert_noop!(
+            <Balances as Currency>::unreserve(&ALICE, &main, 101),
+            Error::<Test>::InsufficientReserved
+        );
+        assert_ok!(<Balances as Currency>::unreserve(&ALICE, &main, 100));
+        assert_eq!(balance(ALICE, &main), INITIAL_BALANCE - 100);
+    });
+}
+
+#[test]
+fn locks_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let main = assets::tok();
+        for id in 0..4u8 {
+            assert_ok!(<Balances as Currency>::set_lock(
+                [id; 8], &ALICE, &main, 100
+            ));
+        }
+        assert_noop!(
+            <Balances as Currency>::set_lock([4; 8], &ALICE, &main, 100),
+            Error::<Test>::TooManyLocks
+        );
+        // Updating an existing lock doesn't take a slot
+        assert_ok!(<Balances as Currency>::set_lock([0; 8], &ALICE, &main, 200));
+    });
+}
+
+#[test]
+fn emptied_accounts_are_reaped() {
+    new_test_ext().execute_with(|| {
+        let holders = HolderCount::<Test>::get(1);
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, btc(), 100));
+        assert_eq!(System::providers(&BOB), 1);
+        assert_eq!(HolderCount::<Test>::get(1), holders + 1);
+
+        assert_ok!(Balances::transfer(Origin::signed(BOB), ALICE, btc(), 100));
+        assert!(!Accounts::<Test>::contains_key(BOB, 1));
+        assert_eq!(System::providers(&BOB), 0);
+        assert_eq!(HolderCount::<Test>::get(1), holders);
+    });
+}
+
+#[test]
+fn accounts_with_consumers_are_not_reaped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, btc(), 100));
+        assert_ok!(System::inc_consumers(&BOB));
+
+        assert_noop!(
+            Balances::transfer(Origin::signed(BOB), ALICE, btc(), 100),
+            DispatchError::ConsumerRemaining
+        );
+    });
+}
+
+#[test]
+fn approved_amounts_can_be_transferred_by_spender() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::approve(Origin::signed(ALICE), BOB, btc(), 100));
+        assert_eq!(<Balances as Currency>::allowance(&ALICE, &BOB, &btc()), 100);
+
+        assert_ok!(Balances::transfer_from(
+            Origin::signed(BOB),
+            ALICE,
+            AUTHOR,
+            btc(),
+            60
+        ));
+        assert_eq!(balance(AUTHOR, &btc()), 60);
+        assert_eq!(<Balances as Currency>::allowance(&ALICE, &BOB, &btc()), 40);
+        assert_noop!(
+            Balances::transfer_from(Origin::signed(BOB), ALICE, AUTHOR, btc(), 41),
+            Error::<Test>::Unapproved
+        );
+
+        assert_ok!(Balances::cancel_approval(Origin::signed(ALICE), BOB, btc()));
+        assert_eq!(<Balances as Currency>::allowance(&ALICE, &BOB, &btc()), 0);
+        assert_noop!(
+            Balances::cancel_approval(Origin::signed(ALICE), BOB, btc()),
+            Error::<Test>::Unapproved
+        );
+    });
+}
+
+#[test]
+fn transfer_batch_is_all_or_nothing() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Balances::transfer_batch(
+                Origin::signed(ALICE),
+                vec![(BOB, btc(), 100), (AUTHOR, btc(), INITIAL_BALANCE)]
+            ),
+            Error::<Test>::Debt
+        );
+        assert_noop!(
+            Balances::transfer_batch(Origin::signed(ALICE), vec![(BOB, btc(), 1); 5]),
+            Error::<Test>::TooManyTransfers
+        );
+
+        assert_ok!(Balances::transfer_batch(
+            Origin::signed(ALICE),
+            vec![(BOB, btc(), 100), (AUTHOR, assets::tok(), 50)]
+        ));
+        assert_eq!(balance(BOB, &btc()), 100);
+        assert_eq!(balance(AUTHOR, &assets::tok()), 50);
+        System::assert_last_event(Event::Balances(BalancesEvent::TransferBatch {
+            from: ALICE,
+            count: 2,
+        }));
+    });
+}
+
+#[test]
+fn transfer_all_respects_keep_alive() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, btc(), 100));
+        assert_noop!(
+            Balances::transfer_keep_alive(Origin::signed(BOB), ALICE, btc(), 100),
+            Error::<Test>::KeepAlive
+        );
+
+        assert_ok!(Balances::transfer_all(
+            Origin::signed(BOB),
+            ALICE,
+            btc(),
+            true
+        ));
+        assert_eq!(balance(BOB, &btc()), 1);
+
+        assert_ok!(Balances::transfer_all(
+            Origin::signed(BOB),
+            ALICE,
+            btc(),
+            false
+        ));
+        assert!(!Accounts::<Test>::contains_key(BOB, 1));
+        assert_eq!(balance(ALICE, &btc()), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn unknown_fee_asset_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let info = DispatchInfo {
+            weight: 1_000,
+            ..Default::default()
+        };
+        let unknown = assets::usd();
+        assert_eq!(
+            ChargeAssetTxPayment::<Test>::from(0, Some(unknown))
+                .pre_dispatch(&ALICE, &remark(), &info, 10)
+                .map(|_| ()),
+            Err(InvalidTransaction::Payment.into())
+        );
+        assert_eq!(balance(ALICE, &assets::tok()), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn frozen_accounts_can_receive_but_not_send() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Balances::freeze(Origin::signed(ALICE), BOB, btc()),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(Balances::freeze(Origin::root(), ALICE, btc()));
+        assert!(<Balances as Currency>::is_frozen(&ALICE, &btc()));
+
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, btc(), 100),
+            Error::<Test>::AccountFrozen
+        );
+        assert_noop!(
+            <Balances as Currency>::burn(&ALICE, &btc(), 100),
+            Error::<Test>::AccountFrozen
+        );
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &btc(), 100));
+        // Other assets of the account are not affected
+        assert_ok!(Balances::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            assets::tok(),
+            100
+        ));
+
+        assert_ok!(Balances::thaw(Origin::root(), ALICE, btc()));
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, btc(), 100));
+    });
+}
@@ -2,12 +2,24 @@
 
 use core::ops::Add;
 
-use frame_support::{dispatch::DispatchResultWithPostInfo, ensure, traits::Get};
+use frame_support::{dispatch::DispatchResultWithPostInfo, ensure, traits::Get, weights::Weight};
 use frame_system::{ensure_signed_or_root, offchain::*};
 pub use pallet::*;
-use primitives::{assets::AssetGetter, currency::Currency, prices::PriceGetter};
+use primitives::{
+    assets::{AssetGetter, AssetIndex, AssetTeam, OnAssetRemove},
+    currency::Currency,
+    prices::PriceGetter,
+};
 use sp_runtime::{traits::Zero, DispatchError, DispatchResult};
-use sp_std::collections::btree_map::BTreeMap;
+use sp_std::{
+    collections::btree_map::{BTreeMap, Entry},
+    prelude::*,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 
 type AssetIdOf<T> = <<T as Config>::Assets as primitives::assets::AssetGetter>::AssetId;
 
@@ -29,7 +41,8 @@ pub mod pallet {
             + MaxEncodedLen
             + Into<u128>
             + From<u128>;
-        type Assets: AssetGetter;
+        type Assets: AssetGetter<AssetError = DispatchError>;
+        type AssetTeam: AssetTeam<Self::AccountId, AssetIdOf<Self>>;
         type Currency: Currency<
             AccountId = Self::AccountId,
             AssetId = AssetIdOf<Self>,
@@ -54,15 +67,27 @@ pub mod pallet {
         _,
         Blake2_128Concat,
         T::AccountId,
-        Blake2_128Concat,
-        AssetIdOf<T>,
+        Twox64Concat,
+        AssetIndex,
         T::Balance,
         ValueQuery,
     >;
 
     #[pallet::storage]
     pub type TotalDeposits<T: Config> =
-        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, T::Balance, ValueQuery>;
+        StorageMap<_, Twox64Concat, AssetIndex, T::Balance, ValueQuery>;
+
+    /// `Deposits` keys by asset, to clean up deposits of a removed asset.
+    #[pallet::storage]
+    pub type AssetDepositors<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetIndex,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -87,6 +112,14 @@ pub mod pallet {
             asset: AssetIdOf<T>,
             amount: T::Balance,
         },
+        /// Deposit of a removed asset paid back as `refund` of the main asset, zero if
+        /// the asset was created by an account or it couldn't be priced or paid.
+        DepositRefunded {
+            who: T::AccountId,
+            asset: AssetIdOf<T>,
+            amount: T::Balance,
+            refund: T::Balance,
+        },
     }
 
     #[pallet::error]
@@ -106,12 +139,14 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
+            let index = T::Assets::index_of(&asset)?;
             T::Currency::burn(&who, &asset, amount)?;
-            let total = Deposits::<T>::mutate(&who, &asset, |total| {
+            let total = Deposits::<T>::mutate(&who, index, |total| {
                 *total += amount;
                 *total
             });
-            TotalDeposits::<T>::mutate(&asset, |total| *total += amount);
+            AssetDepositors::<T>::insert(index, &who, ());
+            TotalDeposits::<T>::mutate(index, |total| *total += amount);
 
             Self::deposit_event(Event::<T>::NewDeposit {
                 who,
@@ -127,10 +162,12 @@ pub mod pallet {
         pub fn withdraw(origin: OriginFor<T>, asset: AssetIdOf<T>) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
-            let amount = Deposits::<T>::take(&who, &asset);
+            let index = T::Assets::index_of(&asset)?;
+            let amount = Deposits::<T>::take(&who, index);
             ensure!(!amount.is_zero(), Error::<T>::NoDeposit);
+            AssetDepositors::<T>::remove(index, &who);
             T::Currency::mint(&who, &asset, amount)?;
-            TotalDeposits::<T>::mutate(&asset, |total| *total -= amount);
+            TotalDeposits::<T>::mutate(index, |total| *total -= amount);
 
             Self::deposit_event(Event::<T>::Withdraw { who, asset, amount });
             Ok(().into())
@@ -158,24 +195,28 @@ pub mod pallet {
         fn on_initialize(n: T::BlockNumber) -> Weight {
             let total_in_stable = Self::total_in_stable();
             if !total_in_stable.is_zero() {
-                let mut issuances = BTreeMap::<AssetIdOf<T>, _>::new();
-                for (who, asset) in Deposits::<T>::iter_keys() {
+                let mut issuances = BTreeMap::<AssetIndex, (AssetIdOf<T>, _, _)>::new();
+                for (who, index) in Deposits::<T>::iter_keys() {
                     let deposit_in_stable = Self::deposit_in_stable(&who);
-                    let issuance = issuances.entry(asset.clone()).or_insert_with(|| {
-                        (
-                            T::Currency::balance(&T::ModuleId::get(), &asset),
-                            T::Balance::zero(),
-                        )
-                    });
+                    let issuance = match issuances.entry(index) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => match T::Assets::id_of(index) {
+                            Ok(asset) => {
+                                let balance = T::Currency::balance(&T::ModuleId::get(), &asset);
+                                entry.insert((asset, balance, T::Balance::zero()))
+                            }
+                            Err(_) => continue,
+                        },
+                    };
 
                     if let Some(to_redistribute) =
-                        Self::multiply_by_rational(issuance.0, deposit_in_stable, total_in_stable)
+                        Self::multiply_by_rational(issuance.1, deposit_in_stable, total_in_stable)
                     {
                         if !to_redistribute.is_zero() {
-                            issuance.1 += to_redistribute;
+                            issuance.2 += to_redistribute;
                             let _ = Self::inner_redistribute(
                                 who.clone(),
-                                asset.clone(),
+                                issuance.0.clone(),
                                 to_redistribute,
                                 n,
                             );
@@ -184,16 +225,11 @@ pub mod pallet {
                 }
 
                 let treasury = T::TreasuryModuleId::get();
-                issuances.into_iter().for_each(
-                    |(asset, (redistribution, actual_redistribution))| {
+                issuances.into_values().for_each(
+                    |(asset, redistribution, actual_redistribution)| {
                         if actual_redistribution < redistribution {
                             let residue = redistribution - actual_redistribution;
-                            let _ = Self::inner_redistribute(
-                                treasury.clone(),
-                                asset.clone(),
-                                residue,
-                                n,
-                            );
+                            let _ = Self::inner_redistribute(treasury.clone(), asset, residue, n);
                         }
                     },
                 );
@@ -207,22 +243,42 @@ pub mod pallet {
 impl<T: Config> Pallet<T> {
     fn total_in_stable() -> T::Balance {
         TotalDeposits::<T>::iter()
-            .filter_map(|(ref asset, amount)| T::Prices::to_stable_amount(asset, amount).ok())
+            .filter_map(|(index, amount)| Self::to_stable_amount(index, amount))
             .fold(T::Balance::zero(), T::Balance::add)
     }
 
     fn deposit_in_stable(who: &T::AccountId) -> T::Balance {
         Deposits::<T>::iter_prefix(who)
-            .filter_map(|(ref asset, amount)| T::Prices::to_stable_amount(asset, amount).ok())
+            .filter_map(|(index, amount)| Self::to_stable_amount(index, amount))
             .fold(T::Balance::zero(), T::Balance::add)
     }
 
+    fn to_stable_amount(index: AssetIndex, amount: T::Balance) -> Option<T::Balance> {
+        let asset = T::Assets::id_of(index).ok()?;
+        T::Prices::to_stable_amount(&asset, amount).ok()
+    }
+
     fn multiply_by_rational(a: T::Balance, b: T::Balance, c: T::Balance) -> Option<T::Balance> {
         sp_runtime::helpers_128bit::multiply_by_rational(a.into(), b.into(), c.into())
             .ok()
             .map(u128::into)
     }
 
+    /// Pays back a deposit of a removed asset from treasury in the main asset at the oracle
+    /// price. Returns the amount paid.
+    ///
+    /// Assets created by an account aren't refunded: the owner picks when to remove them,
+    /// so a made-up price would pay out treasury funds for a worthless asset.
+    fn refund_deposit(who: &T::AccountId, asset: &AssetIdOf<T>, amount: T::Balance) -> T::Balance {
+        if T::AssetTeam::is_owned(asset) {
+            return T::Balance::zero();
+        }
+        let main = T::Assets::get_main();
+        T::Prices::exchange(asset, &main, amount)
+            .and_then(|refund| T::Currency::mint(who, &main, refund).map(|_| refund))
+            .unwrap_or_else(|_| T::Balance::zero())
+    }
+
     fn inner_redistribute(
         who: T::AccountId,
         asset: AssetIdOf<T>,
@@ -234,3 +290,49 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 }
+
+impl<T: Config> OnAssetRemove<AssetIdOf<T>> for Pallet<T> {
+    /// Refunds deposits of the removed asset in the main asset. Needs the asset price,
+    /// so must run before the oracle cleanup. Refunded depositors leave `AssetDepositors`,
+    /// so every call starts from the prefix head.
+    fn on_asset_remove(id: &AssetIdOf<T>, limit: u32) -> (u32, bool, Weight) {
+        let db = T::DbWeight::get();
+        let asset = match T::Assets::index_of(id) {
+            Ok(index) => index,
+            Err(_) => return (0, true, db.reads(1)),
+        };
+
+        let depositors: Vec<_> = AssetDepositors::<T>::iter_key_prefix(asset)
+            .take(limit as usize + 1)
+            .collect();
+        let done = depositors.len() <= limit as usize;
+        let mut count = 0;
+        for who in depositors.into_iter().take(limit as usize) {
+            AssetDepositors::<T>::remove(asset, &who);
+            let amount = Deposits::<T>::take(&who, asset);
+            let refund = Self::refund_deposit(&who, id, amount);
+            Self::deposit_event(Event::<T>::DepositRefunded {
+                who,
+                asset: id.clone(),
+                amount,
+                refund,
+            });
+            count += 1;
+        }
+        // Per depositor: both deposit entries, the team, prices, recipient and treasury
+        let weight = db
+            .reads(count as Weight + 2)
+            .saturating_add(db.reads_writes(6, 4).saturating_mul(count as Weight));
+        (count, done, weight)
+    }
+
+    fn on_asset_removed(asset: &AssetIdOf<T>) -> Weight {
+        let db = T::DbWeight::get();
+        let asset = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => return db.reads(1),
+        };
+        TotalDeposits::<T>::remove(asset);
+        db.reads_writes(1, 1)
+    }
+}
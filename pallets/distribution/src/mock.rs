@@ -0,0 +1,163 @@
+use crate as pallet_distribution;
+use frame_support::{
+    parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, Everything, GenesisBuild},
+};
+use primitives::{
+    assets::{self, AssetData, AssetId},
+    testing::UnitPrices,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::{Header, TestSignature, TestXt, UintAuthorityId},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
+    Perbill,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type Extrinsic = TestXt<Call, ()>;
+pub type AccountId = u64;
+
+pub const ALICE: AccountId = 1;
+pub const DISTRIBUTION: AccountId = 50;
+pub const TREASURY: AccountId = 100;
+pub const INITIAL_BALANCE: u128 = 1_000_000_000_000;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = Extrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Distribution: pallet_distribution::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = UintAuthorityId;
+    type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: UintAuthorityId,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (nonce, ())))
+    }
+}
+
+parameter_types! {
+    pub TokAsset: AssetId = assets::tok();
+    pub const DistributionModuleId: AccountId = DISTRIBUTION;
+    pub const TreasuryModuleId: AccountId = TREASURY;
+    pub const AuthorFeeShare: Perbill = Perbill::from_percent(20);
+}
+
+impl pallet_assets::Config for Test {
+    type Event = Event;
+    type AssetId = AssetId;
+    type AssetData = AssetData;
+    type MainAsset = TokAsset;
+    type OnAssetCreate = (Balances,);
+    type OnAssetRemove = (Distribution, Balances);
+    type RemoveLimit = ConstU32<10>;
+    type Currency = Balances;
+    type AssetDeposit = ConstU128<100>;
+    type OwnerIssuance = ConstU128<1_000_000>;
+    type MaxAssets = ConstU32<8>;
+}
+
+impl pallet_balances::Config for Test {
+    type Event = Event;
+    type Balance = u128;
+    type Assets = Assets;
+    type AssetTeam = Assets;
+    type Prices = UnitPrices;
+    type TreasuryModuleId = TreasuryModuleId;
+    type InitialAssetIssuance = ConstU128<1_000_000_000_000_000>;
+    type MaxLocks = ConstU32<4>;
+    type MaxBatchTransfers = ConstU32<4>;
+    type FindAuthor = ();
+    type AuthorFeeShare = AuthorFeeShare;
+    type OnBalanceChanged = ();
+}
+
+impl pallet_distribution::Config for Test {
+    type Event = Event;
+    type Balance = u128;
+    type Assets = Assets;
+    type AssetTeam = Assets;
+    type Currency = Balances;
+    type Prices = UnitPrices;
+    type ModuleId = DistributionModuleId;
+    type TreasuryModuleId = TreasuryModuleId;
+}
+
+pub fn asset_data() -> AssetData {
+    AssetData::new(b"Test asset", b"TST", 9, 0).expect("static value is valid; qed")
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_assets::GenesisConfig::<Test> {
+        assets: vec![(assets::tok(), asset_data())],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        treasury: vec![assets::tok()],
+        balances: vec![(ALICE, vec![(assets::tok(), INITIAL_BALANCE)])],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
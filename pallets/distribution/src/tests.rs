@@ -0,0 +1,93 @@
+use crate::{mock::*, AssetDepositors, Deposits, Event as DistributionEvent};
+use frame_support::{assert_ok, traits::Hooks};
+use primitives::{
+    assets::{self, AssetId},
+    currency::Currency,
+};
+
+fn gold() -> AssetId {
+    AssetId::from_utf8("gold").expect("static value is valid; qed")
+}
+
+/// Removes `gold` with `origin` and runs its cleanup.
+fn remove_gold(origin: Origin) {
+    assert_ok!(Assets::remove(origin, b"gold".to_vec()));
+    Assets::on_initialize(2);
+    assert_eq!(Deposits::<Test>::iter().count(), 0);
+    assert_eq!(AssetDepositors::<Test>::iter().count(), 0);
+}
+
+#[test]
+fn deposits_of_removed_root_assets_are_refunded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::root(),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &gold(), 1_000));
+        assert_ok!(Distribution::deposit(Origin::signed(ALICE), gold(), 1_000));
+
+        remove_gold(Origin::root());
+
+        assert_eq!(
+            Balances::balance(&ALICE, &assets::tok()),
+            INITIAL_BALANCE + 1_000
+        );
+        System::assert_has_event(
+            DistributionEvent::DepositRefunded {
+                who: ALICE,
+                asset: gold(),
+                amount: 1_000,
+                refund: 1_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn deposits_of_removed_owned_assets_are_not_refunded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::signed(ALICE),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+        assert_ok!(Distribution::deposit(Origin::signed(ALICE), gold(), 1_000));
+
+        remove_gold(Origin::signed(ALICE));
+
+        // The asset deposit is back, nothing is paid for the made-up asset
+        assert_eq!(Balances::balance(&ALICE, &assets::tok()), INITIAL_BALANCE);
+        System::assert_has_event(
+            DistributionEvent::DepositRefunded {
+                who: ALICE,
+                asset: gold(),
+                amount: 1_000,
+                refund: 0,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn withdraw_leaves_asset_depositors() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::create(
+            Origin::root(),
+            b"gold".to_vec(),
+            asset_data()
+        ));
+        assert_ok!(<Balances as Currency>::mint(&ALICE, &gold(), 1_000));
+        assert_ok!(Distribution::deposit(Origin::signed(ALICE), gold(), 1_000));
+        assert_eq!(AssetDepositors::<Test>::iter().count(), 1);
+
+        assert_ok!(Distribution::withdraw(Origin::signed(ALICE), gold()));
+        assert_eq!(AssetDepositors::<Test>::iter().count(), 0);
+
+        remove_gold(Origin::root());
+        assert_eq!(Balances::balance(&ALICE, &assets::tok()), INITIAL_BALANCE);
+    });
+}
@@ -1,17 +1,29 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
-use frame_support::{
-    ensure,
-    traits::{Get, Hooks, Randomness},
-    weights::Weight,
-};
+use frame_support::{ensure, traits::Get, weights::Weight};
+use frame_system::offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer};
 pub use pallet::*;
-use primitives::{assets::AssetGetter, prices::PriceGetter};
+use primitives::{
+    assets::{AssetGetter, AssetIndex, OnAssetRemove},
+    prices::{PriceGetter, Rounding},
+};
+use sp_core::U256;
 use sp_runtime::{
-    traits::{CheckedDiv, One, TrailingZeroInput, Zero},
+    offchain::http,
+    traits::{One, Saturating, UniqueSaturatedInto, Zero},
     DispatchError, DispatchResult, FixedPointNumber, FixedPointOperand, KeyTypeId,
 };
+use sp_std::prelude::*;
+
+pub mod offchain;
+pub mod source;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use source::{FixedPrice, PriceSource, RandomPrice, ScheduledPrice};
 
 type AssetIdOf<T> = <<T as Config>::Assets as primitives::assets::AssetGetter>::AssetId;
 
@@ -25,6 +37,17 @@ pub mod crypto {
 
     pub type AuthoritySignature = sp_core::sr25519::Signature;
     pub type AuthorityId = sp_core::sr25519::Public;
+
+    /// Signs price feed transactions submitted by the offchain worker with the `orac` key.
+    pub struct OracleAuthId;
+
+    impl frame_system::offchain::AppCrypto<sp_runtime::MultiSigner, sp_runtime::MultiSignature>
+        for OracleAuthId
+    {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = AuthoritySignature;
+        type GenericPublic = AuthorityId;
+    }
 }
 
 #[frame_support::pallet]
@@ -34,7 +57,7 @@ pub mod pallet {
     use frame_system::pallet_prelude::*;
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type Balance: Parameter
             + Member
@@ -51,8 +74,17 @@ pub mod pallet {
             + FixedPointNumber;
         type Assets: AssetGetter<AssetError = DispatchError>;
         type StableAsset: Get<AssetIdOf<Self>>;
-        type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
-        type Precision: Get<Self::Price>;
+        type PriceSource: PriceSource<AssetIdOf<Self>, Self::BlockNumber, Self::Price>;
+        /// Prices are updated every `UpdatePeriod` blocks, zero disables periodic updates.
+        type UpdatePeriod: Get<Self::BlockNumber>;
+        /// Maximum relative price change per block, measured against the lower of the old and
+        /// new prices (e.g. 0.5 allows a 50% rise or a 1/3 drop). Larger moves are quarantined
+        /// until confirmed by root. Zero disables the check.
+        type MaxPriceChange: Get<Self::Price>;
+        /// Maximum number of prices scheduled in genesis for a single block.
+        #[pallet::constant]
+        type MaxScheduledPrices: Get<u32>;
+        type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
     }
 
     #[pallet::pallet]
@@ -60,27 +92,75 @@ pub mod pallet {
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
-    pub type Prices<T: Config> =
-        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, T::Price, OptionQuery>;
+    pub type Prices<T: Config> = StorageMap<_, Twox64Concat, AssetIndex, T::Price, OptionQuery>;
+
+    /// Prices rejected by the circuit breaker, waiting for root confirmation.
+    #[pallet::storage]
+    pub type PendingPrices<T: Config> =
+        StorageMap<_, Twox64Concat, AssetIndex, T::Price, OptionQuery>;
+
+    /// Price of an asset at the start of the block it was last updated in. All updates
+    /// within a block are checked by the circuit breaker against it.
+    #[pallet::storage]
+    pub type BlockPrices<T: Config> =
+        StorageMap<_, Twox64Concat, AssetIndex, (T::BlockNumber, T::Price), OptionQuery>;
+
+    /// Accounts allowed to submit prices via `feed_prices`.
+    #[pallet::storage]
+    pub type Feeders<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Whether the `RandomPrice` source generates prices. Set in genesis of dev chains only,
+    /// other chains are fed by the offchain worker.
+    #[pallet::storage]
+    pub type RandomPrices<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Prices scripted in genesis, served by the `ScheduledPrice` source.
+    #[pallet::storage]
+    pub type Schedule<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<(AssetIdOf<T>, T::Price), T::MaxScheduledPrices>,
+        ValueQuery,
+    >;
 
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub prices: Vec<(AssetIdOf<T>, T::Price)>,
+        pub feeders: Vec<T::AccountId>,
+        pub random_prices: bool,
+        pub schedule: Vec<(T::BlockNumber, AssetIdOf<T>, T::Price)>,
     }
 
     #[cfg(feature = "std")]
     impl<T: Config> Default for GenesisConfig<T> {
         fn default() -> Self {
-            Self { prices: vec![] }
+            Self {
+                prices: vec![],
+                feeders: vec![],
+                random_prices: false,
+                schedule: vec![],
+            }
         }
     }
 
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
-            Prices::<T>::insert(T::StableAsset::get(), T::Price::one());
+            if let Ok(stable) = T::Assets::index_of(&T::StableAsset::get()) {
+                Prices::<T>::insert(stable, T::Price::one());
+            }
             for (asset, price) in &self.prices {
-                Prices::<T>::insert(asset, price);
+                let index = T::Assets::index_of(asset).expect("Unknown price asset");
+                Prices::<T>::insert(index, price);
+            }
+            for who in &self.feeders {
+                Feeders::<T>::insert(who, ());
+            }
+            RandomPrices::<T>::put(self.random_prices);
+            for (n, asset, price) in &self.schedule {
+                Schedule::<T>::try_append(n, (asset.clone(), price.clone()))
+                    .expect("Too many prices scheduled for a block");
             }
         }
     }
@@ -92,6 +172,16 @@ pub mod pallet {
             asset: AssetIdOf<T>,
             price: T::Price,
         },
+        FeederSet {
+            who: T::AccountId,
+            enabled: bool,
+        },
+        /// `price` is too far from `last_price`, the price at the start of the block.
+        PriceRejected {
+            asset: AssetIdOf<T>,
+            price: T::Price,
+            last_price: T::Price,
+        },
     }
 
     #[pallet::error]
@@ -100,6 +190,8 @@ pub mod pallet {
         SetPriceForStableAsset,
         SetZeroPrice,
         Math,
+        NotFeeder,
+        NoPendingPrice,
     }
 
     #[pallet::call]
@@ -111,7 +203,49 @@ pub mod pallet {
             price: T::Price,
         ) -> DispatchResultWithPostInfo {
             ensure_root(origin)?;
-            Self::set_price(asset, price)?;
+            Self::force_price(asset, price)?;
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn confirm_price(
+            origin: OriginFor<T>,
+            asset: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            let index = T::Assets::index_of(&asset)?;
+            let price = PendingPrices::<T>::get(index).ok_or(Error::<T>::NoPendingPrice)?;
+            Self::force_price(asset, price)?;
+            Ok(().into())
+        }
+
+        #[frame_support::transactional]
+        #[pallet::weight((prices.len() as Weight).saturating_mul(10_000))]
+        pub fn feed_prices(
+            origin: OriginFor<T>,
+            prices: Vec<(AssetIdOf<T>, T::Price)>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(Feeders::<T>::contains_key(&who), Error::<T>::NotFeeder);
+            for (asset, price) in prices {
+                Self::set_price(asset, price)?;
+            }
+            Ok(().into())
+        }
+
+        #[pallet::weight(10_000)]
+        pub fn set_feeder(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            enabled: bool,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            if enabled {
+                Feeders::<T>::insert(&who, ());
+            } else {
+                Feeders::<T>::remove(&who);
+            }
+            Self::deposit_event(Event::<T>::FeederSet { who, enabled });
             Ok(().into())
         }
     }
@@ -119,56 +253,180 @@ pub mod pallet {
     #[pallet::hooks]
     impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
         fn on_initialize(n: T::BlockNumber) -> Weight {
-            if (n % T::BlockNumber::from(5u8)).is_zero() {
+            let mut weight = 0;
+            if Self::is_update_block(n) {
                 for asset in T::Assets::get_all_in_ord() {
                     if asset == T::StableAsset::get() {
                         continue;
                     }
-                    let price = Self::gen_price(("pallet-oracle", n, &asset));
+                    let price = match T::PriceSource::price(n, &asset) {
+                        Some(price) if <Self as PriceGetter>::get(&asset).ok() != Some(price) => {
+                            price
+                        }
+                        _ => continue,
+                    };
                     if let Err(e) = Self::set_price(asset, price) {
                         frame_support::runtime_print!("SetPriceError: {:?}", e);
                     }
                 }
-                10_000
-            } else {
-                0
+                weight += 10_000;
+            }
+
+            // Scripted prices go last so they win over the periodic source, and skip the
+            // circuit breaker so they land exactly at their block
+            let scheduled = T::PriceSource::scheduled(n);
+            weight += (scheduled.len() as Weight).saturating_mul(10_000);
+            for (asset, price) in scheduled {
+                if let Err(e) = Self::force_price(asset, price) {
+                    frame_support::runtime_print!("SetPriceError: {:?}", e);
+                }
+            }
+            weight
+        }
+
+        fn offchain_worker(n: T::BlockNumber) {
+            if !Self::is_update_block(n) {
+                return;
+            }
+            if let Err(e) = Self::fetch_and_submit_prices() {
+                frame_support::runtime_print!("OffchainPriceError: {:?}", e);
             }
         }
     }
 }
 
 impl<T: Config> Pallet<T> {
+    fn is_update_block(n: T::BlockNumber) -> bool {
+        let period = T::UpdatePeriod::get();
+        !period.is_zero() && (n % period).is_zero()
+    }
+
+    /// Sets price through the circuit breaker.
     fn set_price(asset: AssetIdOf<T>, price: T::Price) -> DispatchResult {
+        let index = Self::validate_price(&asset, &price)?;
+        if let Some(last_price) = Self::block_start_price(index) {
+            if Self::exceeds_max_change(last_price, price) {
+                PendingPrices::<T>::insert(index, &price);
+                Self::deposit_event(Event::<T>::PriceRejected {
+                    asset,
+                    price,
+                    last_price,
+                });
+                return Ok(());
+            }
+        }
+        Self::do_set_price(asset, index, price);
+        Ok(())
+    }
+
+    /// Sets price bypassing the circuit breaker, later updates in the block are checked
+    /// against it.
+    fn force_price(asset: AssetIdOf<T>, price: T::Price) -> DispatchResult {
+        let index = Self::validate_price(&asset, &price)?;
+        BlockPrices::<T>::insert(index, (frame_system::Pallet::<T>::block_number(), price));
+        Self::do_set_price(asset, index, price);
+        Ok(())
+    }
+
+    /// Price of the asset before its first update in the current block, so that several
+    /// updates in a block can't compound past the maximum change.
+    fn block_start_price(index: AssetIndex) -> Option<T::Price> {
+        let now = frame_system::Pallet::<T>::block_number();
+        match BlockPrices::<T>::get(index) {
+            Some((n, price)) if n == now => Some(price),
+            _ => {
+                let price = Prices::<T>::get(index)?;
+                BlockPrices::<T>::insert(index, (now, price));
+                Some(price)
+            }
+        }
+    }
+
+    /// Checks the price can be set and returns the asset index to store it under.
+    fn validate_price(asset: &AssetIdOf<T>, price: &T::Price) -> Result<AssetIndex, DispatchError> {
         ensure!(
-            asset != T::StableAsset::get() || <T::Price as One>::is_one(&price),
+            asset != &T::StableAsset::get() || <T::Price as One>::is_one(price),
             Error::<T>::SetPriceForStableAsset,
         );
         ensure!(
-            !<T::Price as Zero>::is_zero(&price),
+            !<T::Price as Zero>::is_zero(price),
             Error::<T>::SetZeroPrice,
         );
-        T::Assets::check(&asset)?;
-        Prices::<T>::insert(&asset, &price);
+        T::Assets::check(asset)?;
+        T::Assets::index_of(asset)
+    }
 
+    fn do_set_price(asset: AssetIdOf<T>, index: AssetIndex, price: T::Price) {
+        Prices::<T>::insert(index, &price);
+        PendingPrices::<T>::remove(index);
         Self::deposit_event(Event::<T>::UpdatePrice { asset, price });
-        Ok(())
     }
 
-    fn gen_price<S: Encode>(seed: S) -> T::Price {
-        let mut i = 0u32;
-        loop {
-            let bytes = T::Randomness::random(&(&seed, i).encode()[..]).0;
-            let mut bytes = bytes.as_ref()[0..8].to_vec();
-            bytes[7] &= 0x0f;
-            if let Ok(delta) = <T::Price as Decode>::decode(&mut TrailingZeroInput::new(&bytes[..]))
-            {
-                if delta < <T::Price as One>::one() {
-                    let precision = T::Precision::get();
-                    break <T::Price as One>::one() + (delta * precision) / precision;
-                }
+    fn exceeds_max_change(last_price: T::Price, price: T::Price) -> bool {
+        let max_change = T::MaxPriceChange::get();
+        if <T::Price as Zero>::is_zero(&max_change) {
+            return false;
+        }
+        // Measured against the lower price, so that drops are limited as well as rises
+        let (low, high) = if price > last_price {
+            (last_price, price)
+        } else {
+            (price, last_price)
+        };
+        high - low > low.saturating_mul(max_change)
+    }
+
+    /// Inner value of `price`, `Error::Math` if it doesn't fit into `u128`.
+    fn price_to_u128(price: T::Price) -> Result<u128, DispatchError> {
+        let inner = price.into_inner();
+        // Saturation is lossy, so the value fits only if it converts back unchanged
+        let value: u128 = inner.unique_saturated_into();
+        match <T::Price as FixedPointNumber>::Inner::try_from(value) {
+            Ok(back) if back == inner => Ok(value),
+            _ => Err(Error::<T>::Math.into()),
+        }
+    }
+
+    fn fetch_and_submit_prices() -> Result<(), &'static str> {
+        let url = match offchain::price_source_url() {
+            Some(url) => url,
+            None => return Ok(()),
+        };
+        let signer = Signer::<T, T::AuthorityId>::all_accounts();
+        if !signer.can_sign() {
+            return Err("No oracle keys available");
+        }
+
+        let prices = Self::fetch_prices(&url).map_err(|_| "Failed to fetch prices")?;
+        if prices.is_empty() {
+            return Ok(());
+        }
+
+        let results = signer.send_signed_transaction(|_| Call::feed_prices {
+            prices: prices.clone(),
+        });
+        for (_, result) in &results {
+            if result.is_err() {
+                return Err("Failed to submit prices");
             }
-            i += 1;
         }
+        Ok(())
+    }
+
+    fn fetch_prices(url: &[u8]) -> Result<Vec<(AssetIdOf<T>, T::Price)>, http::Error> {
+        let prices = offchain::fetch_quotes(url)?
+            .into_iter()
+            .filter_map(|quote| {
+                let asset = AssetIdOf::<T>::try_from(quote.symbol).ok()?;
+                if asset == T::StableAsset::get() || T::Assets::check(&asset).is_err() {
+                    return None;
+                }
+                let denominator = 10u128.checked_pow(quote.decimals)?;
+                let price = T::Price::checked_from_rational(quote.mantissa, denominator)?;
+                (!<T::Price as Zero>::is_zero(&price)).then_some((asset, price))
+            })
+            .collect();
+        Ok(prices)
     }
 }
 
@@ -182,7 +440,10 @@ impl<T: Config> PriceGetter for Pallet<T> {
         if id == &T::StableAsset::get() {
             Ok(T::Price::one())
         } else {
-            Prices::<T>::get(id).ok_or(Error::<T>::NoPrice.into())
+            T::Assets::index_of(id)
+                .ok()
+                .and_then(Prices::<T>::get)
+                .ok_or(Error::<T>::NoPrice.into())
         }
     }
 
@@ -190,13 +451,53 @@ impl<T: Config> PriceGetter for Pallet<T> {
         T::StableAsset::get()
     }
 
-    fn exchange(
+    fn exchange_with_rounding(
         from: &AssetIdOf<T>,
         to: &AssetIdOf<T>,
         amount: T::Balance,
+        rounding: Rounding,
     ) -> Result<T::Balance, DispatchError> {
-        let price = (Self::get(from)?, Self::get(to)?);
-        let rel_price = price.0.checked_div(&price.1).ok_or(Error::<T>::Math)?;
-        Ok(rel_price.saturating_mul_int(amount))
+        let price_from = Self::price_to_u128(Self::get(from)?)?;
+        let price_to = Self::price_to_u128(Self::get(to)?)?;
+        let amount: u128 = amount.try_into().map_err(|_| Error::<T>::Math)?;
+        ensure!(price_to != 0, Error::<T>::Math);
+
+        // amount * price_from can't overflow 256 bits
+        let price_to = U256::from(price_to);
+        let (mut result, rem) = (U256::from(amount) * U256::from(price_from)).div_mod(price_to);
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => !rem.is_zero(),
+            Rounding::Nearest => rem + rem >= price_to,
+        };
+        if round_up {
+            result += U256::one();
+        }
+
+        ensure!(result <= U256::from(u128::MAX), Error::<T>::Math);
+        T::Balance::try_from(result.low_u128()).map_err(|_| Error::<T>::Math.into())
+    }
+}
+
+impl<T: Config> OnAssetRemove<AssetIdOf<T>> for Pallet<T> {
+    fn on_asset_remove(asset: &AssetIdOf<T>, limit: u32) -> (u32, bool, Weight) {
+        let db = T::DbWeight::get();
+        let asset = match T::Assets::index_of(asset) {
+            Ok(index) => index,
+            Err(_) => return (0, true, db.reads(1)),
+        };
+        if !Prices::<T>::contains_key(asset)
+            && !PendingPrices::<T>::contains_key(asset)
+            && !BlockPrices::<T>::contains_key(asset)
+        {
+            return (0, true, db.reads(4));
+        }
+        if limit < 3 {
+            return (0, false, db.reads(4));
+        }
+        Prices::<T>::remove(asset);
+        PendingPrices::<T>::remove(asset);
+        BlockPrices::<T>::remove(asset);
+        (3, true, db.reads_writes(4, 3))
     }
 }
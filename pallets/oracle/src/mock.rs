@@ -0,0 +1,140 @@
+use crate as pallet_oracle;
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, ConstU64, Everything, GenesisBuild},
+};
+use primitives::{
+    assets::{self, AssetId},
+    testing,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::{Header, TestXt},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+    FixedU128, MultiSignature,
+};
+
+pub use primitives::testing::btc;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type Extrinsic = TestXt<Call, ()>;
+pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type Price = FixedU128;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = Extrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Oracle: pallet_oracle::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = <MultiSignature as Verify>::Signer;
+    type Signature = MultiSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: <MultiSignature as Verify>::Signer,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (nonce, ())))
+    }
+}
+
+parameter_types! {
+    /// Main asset, stable asset and `btc`, indexed in this order.
+    pub TestAssetList: Vec<AssetId> = vec![assets::tok(), assets::usd(), btc()];
+}
+
+pub type TestAssets = testing::TestAssets<TestAssetList>;
+
+parameter_types! {
+    pub StableAsset: AssetId = assets::usd();
+    /// 50%
+    pub const MaxPriceChange: Price = Price::from_inner(500_000_000_000_000_000);
+    pub const PeriodicPrice: Price = Price::from_inner(2_000_000_000_000_000_000);
+}
+
+impl pallet_oracle::Config for Test {
+    type Event = Event;
+    type Balance = u128;
+    type Price = Price;
+    type Assets = TestAssets;
+    type StableAsset = StableAsset;
+    type PriceSource = (
+        pallet_oracle::ScheduledPrice<Test>,
+        pallet_oracle::FixedPrice<PeriodicPrice>,
+    );
+    type UpdatePeriod = ConstU64<5>;
+    type MaxPriceChange = MaxPriceChange;
+    type MaxScheduledPrices = ConstU32<2>;
+    type AuthorityId = crate::crypto::OracleAuthId;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_oracle::GenesisConfig::<Test> {
+        prices: vec![
+            (assets::tok(), Price::from(2)),
+            (btc(), Price::from(30_000)),
+        ],
+        schedule: vec![
+            (3, btc(), Price::from(60_000)),
+            (5, assets::tok(), Price::from(8)),
+        ],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,165 @@
+//! Price source for the oracle offchain worker.
+//!
+//! The source URL is read from the persistent offchain local storage under
+//! [`PRICE_SOURCE_URL_KEY`] (e.g. via the `offchain_localStorageSet` RPC) and must
+//! respond with a flat JSON object mapping asset symbols to prices:
+//!
+//! ```json
+//! { "coin": 1.25, "btc": "31250.5" }
+//! ```
+
+use sp_runtime::offchain::{http, Duration};
+use sp_std::prelude::*;
+
+/// Offchain local storage key holding the raw (not SCALE-encoded) price source URL.
+pub const PRICE_SOURCE_URL_KEY: &[u8] = b"oracle::price-source-url";
+
+const FETCH_TIMEOUT_MS: u64 = 2_000;
+
+/// A decimal price quoted by the source: `mantissa / 10^decimals`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub symbol: Vec<u8>,
+    pub mantissa: u128,
+    pub decimals: u32,
+}
+
+pub fn price_source_url() -> Option<Vec<u8>> {
+    sp_io::offchain::local_storage_get(
+        sp_core::offchain::StorageKind::PERSISTENT,
+        PRICE_SOURCE_URL_KEY,
+    )
+}
+
+pub fn fetch_quotes(url: &[u8]) -> Result<Vec<Quote>, http::Error> {
+    let url = sp_std::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+    let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+    let pending = http::Request::get(url)
+        .deadline(deadline)
+        .send()
+        .map_err(|_| http::Error::IoError)?;
+    let response = pending
+        .try_wait(deadline)
+        .map_err(|_| http::Error::DeadlineReached)??;
+    if response.code != 200 {
+        return Err(http::Error::Unknown);
+    }
+
+    let body = response.body().collect::<Vec<u8>>();
+    parse_quotes(&body).ok_or(http::Error::Unknown)
+}
+
+/// Parses a flat JSON object of `"symbol": number` (or `"symbol": "number"`) pairs.
+pub fn parse_quotes(body: &[u8]) -> Option<Vec<Quote>> {
+    let mut input = Input { body, pos: 0 };
+    let mut quotes = Vec::new();
+
+    input.expect(b'{')?;
+    if input.peek()? == b'}' {
+        input.pos += 1;
+        return input.finish().map(|_| quotes);
+    }
+
+    loop {
+        let symbol = input.string()?.to_vec();
+        input.expect(b':')?;
+        let (mantissa, decimals) = if input.peek()? == b'"' {
+            parse_decimal(input.string()?)?
+        } else {
+            parse_decimal(input.number())?
+        };
+        quotes.push(Quote {
+            symbol,
+            mantissa,
+            decimals,
+        });
+
+        match input.next()? {
+            b',' => continue,
+            b'}' => break,
+            _ => return None,
+        }
+    }
+
+    input.finish().map(|_| quotes)
+}
+
+fn parse_decimal(raw: &[u8]) -> Option<(u128, u32)> {
+    let mut parts = raw.splitn(2, |c| *c == b'.');
+    let integer = parts.next().filter(|p| !p.is_empty())?;
+    let fraction = parts.next().unwrap_or_default();
+
+    let mut mantissa = 0u128;
+    for digit in integer.iter().chain(fraction) {
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+        mantissa = mantissa
+            .checked_mul(10)?
+            .checked_add((digit - b'0') as u128)?;
+    }
+
+    Some((mantissa, fraction.len() as u32))
+}
+
+struct Input<'a> {
+    body: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .body
+            .get(self.pos)
+            .map_or(false, |c| c.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.body.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn expect(&mut self, c: u8) -> Option<()> {
+        (self.next()? == c).then_some(())
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        let len = self.body[start..].iter().position(|c| *c == b'"')?;
+        let value = &self.body[start..start + len];
+        if value.contains(&b'\\') {
+            return None;
+        }
+        self.pos = start + len + 1;
+        Some(value)
+    }
+
+    fn number(&mut self) -> &'a [u8] {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .body
+            .get(self.pos)
+            .map_or(false, |c| c.is_ascii_digit() || *c == b'.')
+        {
+            self.pos += 1;
+        }
+        &self.body[start..self.pos]
+    }
+
+    fn finish(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        (self.pos == self.body.len()).then_some(())
+    }
+}
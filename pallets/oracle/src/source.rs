@@ -0,0 +1,106 @@
+//! Price sources queried by the oracle every `UpdatePeriod` blocks.
+
+use crate::{AssetIdOf, Config, RandomPrices, Schedule};
+use codec::{Decode, Encode};
+use frame_support::traits::{Get, Randomness};
+use sp_runtime::traits::{One, TrailingZeroInput};
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub trait PriceSource<AssetId, BlockNumber, Price> {
+    /// Returns the new price of `asset` at block `n`, `None` keeps the current price.
+    fn price(n: BlockNumber, asset: &AssetId) -> Option<Price>;
+
+    /// Prices scripted for block `n`, queried every block and applied after `price`.
+    fn scheduled(_n: BlockNumber) -> Vec<(AssetId, Price)> {
+        Vec::new()
+    }
+}
+
+/// Feeder-driven source: prices only change via `feed_prices` or `force_set_price`.
+impl<AssetId, BlockNumber, Price> PriceSource<AssetId, BlockNumber, Price> for () {
+    fn price(_n: BlockNumber, _asset: &AssetId) -> Option<Price> {
+        None
+    }
+}
+
+/// Sets every asset to the same constant price.
+pub struct FixedPrice<P>(PhantomData<P>);
+
+impl<AssetId, BlockNumber, Price, P: Get<Price>> PriceSource<AssetId, BlockNumber, Price>
+    for FixedPrice<P>
+{
+    fn price(_n: BlockNumber, _asset: &AssetId) -> Option<Price> {
+        Some(P::get())
+    }
+}
+
+/// Prices scripted in genesis `schedule`, for reproducible test runs.
+pub struct ScheduledPrice<T>(PhantomData<T>);
+
+impl<T: Config> PriceSource<AssetIdOf<T>, T::BlockNumber, T::Price> for ScheduledPrice<T> {
+    fn price(_n: T::BlockNumber, _asset: &AssetIdOf<T>) -> Option<T::Price> {
+        None
+    }
+
+    fn scheduled(n: T::BlockNumber) -> Vec<(AssetIdOf<T>, T::Price)> {
+        Schedule::<T>::take(n).into_inner()
+    }
+}
+
+/// Random price in range [1.0, 2.0] rounded to `Precision`. Only active when
+/// `RandomPrices` is enabled in genesis, so the same runtime can serve dev and live chains.
+pub struct RandomPrice<T, R, Precision>(PhantomData<(T, R, Precision)>);
+
+impl<T, R, Precision> PriceSource<AssetIdOf<T>, T::BlockNumber, T::Price>
+    for RandomPrice<T, R, Precision>
+where
+    T: Config,
+    R: Randomness<T::Hash, T::BlockNumber>,
+    Precision: Get<T::Price>,
+{
+    fn price(n: T::BlockNumber, asset: &AssetIdOf<T>) -> Option<T::Price> {
+        RandomPrices::<T>::get().then(|| Self::gen_price(("pallet-oracle", n, asset)))
+    }
+}
+
+impl<T, R, Precision> RandomPrice<T, R, Precision>
+where
+    T: Config,
+    R: Randomness<T::Hash, T::BlockNumber>,
+    Precision: Get<T::Price>,
+{
+    fn gen_price<S: Encode>(seed: S) -> T::Price {
+        let mut i = 0u32;
+        loop {
+            let bytes = R::random(&(&seed, i).encode()[..]).0;
+            let mut bytes = bytes.as_ref()[0..8].to_vec();
+            bytes[7] &= 0x0f;
+            if let Ok(delta) = <T::Price as Decode>::decode(&mut TrailingZeroInput::new(&bytes[..]))
+            {
+                if delta < <T::Price as One>::one() {
+                    let precision = Precision::get();
+                    break <T::Price as One>::one() + (delta * precision) / precision;
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 5)]
+impl<AssetId, BlockNumber: Copy, Price> PriceSource<AssetId, BlockNumber, Price> for Tuple {
+    fn price(n: BlockNumber, asset: &AssetId) -> Option<Price> {
+        for_tuples!(#(
+            if let Some(price) = Tuple::price(n, asset) {
+                return Some(price);
+            }
+        )*);
+        None
+    }
+
+    fn scheduled(n: BlockNumber) -> Vec<(AssetId, Price)> {
+        let mut prices = Vec::new();
+        for_tuples!(#( prices.extend(Tuple::scheduled(n)); )*);
+        prices
+    }
+}
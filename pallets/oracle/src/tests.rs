@@ -0,0 +1,446 @@
+use crate::{mock::*, offchain, Error, Feeders, PendingPrices, Prices};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use primitives::{
+    assets,
+    prices::{PriceGetter, Rounding},
+};
+use sp_core::offchain::{
+    testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+    Externalities, HttpError, HttpRequestId, HttpRequestStatus, OffchainDbExt, OffchainWorkerExt,
+    OpaqueNetworkState, OpaquePeerId, StorageKind, Timestamp, TransactionPoolExt,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{offchain::http, traits::Zero, FixedPointNumber};
+use std::sync::Arc;
+
+const PRICE_SOURCE_URL: &str = "http://localhost:8000/prices";
+
+/// `TestOffchainExt` finishing every HTTP request with `status`, `None` for a timeout.
+struct HttpStatusExt {
+    inner: TestOffchainExt,
+    status: Option<u16>,
+}
+
+impl Externalities for HttpStatusExt {
+    fn is_validator(&self) -> bool {
+        self.inner.is_validator()
+    }
+
+    fn network_state(&self) -> Result<OpaqueNetworkState, ()> {
+        self.inner.network_state()
+    }
+
+    fn timestamp(&mut self) -> Timestamp {
+        self.inner.timestamp()
+    }
+
+    fn sleep_until(&mut self, deadline: Timestamp) {
+        self.inner.sleep_until(deadline)
+    }
+
+    fn random_seed(&mut self) -> [u8; 32] {
+        self.inner.random_seed()
+    }
+
+    fn http_request_start(
+        &mut self,
+        method: &str,
+        uri: &str,
+        meta: &[u8],
+    ) -> Result<HttpRequestId, ()> {
+        self.inner.http_request_start(method, uri, meta)
+    }
+
+    fn http_request_add_header(
+        &mut self,
+        request_id: HttpRequestId,
+        name: &str,
+        value: &str,
+    ) -> Result<(), ()> {
+        self.inner.http_request_add_header(request_id, name, value)
+    }
+
+    fn http_request_write_body(
+        &mut self,
+        request_id: HttpRequestId,
+        chunk: &[u8],
+        deadline: Option<Timestamp>,
+    ) -> Result<(), HttpError> {
+        self.inner
+            .http_request_write_body(request_id, chunk, deadline)
+    }
+
+    fn http_response_wait(
+        &mut self,
+        ids: &[HttpRequestId],
+        _deadline: Option<Timestamp>,
+    ) -> Vec<HttpRequestStatus> {
+        ids.iter()
+            .map(|_| match self.status {
+                Some(code) => HttpRequestStatus::Finished(code),
+                None => HttpRequestStatus::DeadlineReached,
+            })
+            .collect()
+    }
+
+    fn http_response_headers(&mut self, request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.inner.http_response_headers(request_id)
+    }
+
+    fn http_response_read_body(
+        &mut self,
+        request_id: HttpRequestId,
+        buffer: &mut [u8],
+        deadline: Option<Timestamp>,
+    ) -> Result<usize, HttpError> {
+        self.inner
+            .http_response_read_body(request_id, buffer, deadline)
+    }
+
+    fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
+        self.inner.set_authorized_nodes(nodes, authorized_only)
+    }
+}
+
+/// Runs `test` with an `orac` key in the keystore and the price source answering `requests`
+/// requests with `body` and `status`. Returns its result and the transactions submitted.
+fn with_price_source<R>(
+    body: &[u8],
+    status: Option<u16>,
+    requests: usize,
+    test: impl FnOnce() -> R,
+) -> (R, Vec<Vec<u8>>) {
+    let (offchain_ext, offchain_state) = TestOffchainExt::new();
+    let (pool_ext, pool_state) = TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(&keystore, crate::KEY_TYPE, Some("//Oracle")).unwrap();
+    for _ in 0..requests {
+        offchain_state.write().expect_request(PendingRequest {
+            method: "GET".into(),
+            uri: PRICE_SOURCE_URL.into(),
+            response: Some(body.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+    }
+
+    let mut ext = new_test_ext();
+    ext.register_extension(OffchainDbExt::new(offchain_ext.clone()));
+    ext.register_extension(OffchainWorkerExt::new(HttpStatusExt {
+        inner: offchain_ext,
+        status,
+    }));
+    ext.register_extension(TransactionPoolExt::new(pool_ext));
+    ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+    let result = ext.execute_with(|| {
+        sp_io::offchain::local_storage_set(
+            StorageKind::PERSISTENT,
+            offchain::PRICE_SOURCE_URL_KEY,
+            PRICE_SOURCE_URL.as_bytes(),
+        );
+        test()
+    });
+    let transactions = pool_state.read().transactions.clone();
+    (result, transactions)
+}
+
+#[test]
+fn parse_quotes_reads_numbers_and_strings() {
+    assert_eq!(
+        offchain::parse_quotes(br#" { "coin": 1.25, "btc" : "31250.5" } "#),
+        Some(vec![
+            offchain::Quote {
+                symbol: b"coin".to_vec(),
+                mantissa: 125,
+                decimals: 2,
+            },
+            offchain::Quote {
+                symbol: b"btc".to_vec(),
+                mantissa: 312_505,
+                decimals: 1,
+            },
+        ])
+    );
+    assert_eq!(offchain::parse_quotes(b"{}"), Some(vec![]));
+}
+
+#[test]
+fn parse_quotes_rejects_malformed_json() {
+    assert_eq!(offchain::parse_quotes(br#"{"coin": 1.25"#), None);
+    assert_eq!(offchain::parse_quotes(br#"{"coin": -1}"#), None);
+    assert_eq!(offchain::parse_quotes(br#"{"coin": 1.2.5}"#), None);
+    assert_eq!(offchain::parse_quotes(br#"{"coin": 1} trailing"#), None);
+    assert_eq!(offchain::parse_quotes(br#"["coin", 1]"#), None);
+}
+
+#[test]
+fn offchain_worker_submits_fetched_prices() {
+    let body = br#"{"coin": 2.5, "btc": "31250.5", "usd": 1, "eth": 1800}"#;
+    let (_, transactions) = with_price_source(body, Some(200), 1, || Oracle::offchain_worker(5));
+
+    assert_eq!(transactions.len(), 1);
+    let tx = Extrinsic::decode(&mut &transactions[0][..]).unwrap();
+    assert_eq!(tx.signature, Some((0, ())));
+    // The stable asset and unknown assets are skipped
+    assert_eq!(
+        tx.call,
+        Call::Oracle(crate::Call::feed_prices {
+            prices: vec![
+                (assets::tok(), Price::saturating_from_rational(25, 10)),
+                (btc(), Price::saturating_from_rational(312_505, 10)),
+            ],
+        })
+    );
+}
+
+#[test]
+fn malformed_payload_submits_nothing() {
+    let ((quotes, submitted), transactions) =
+        with_price_source(br#"{"btc": 31250.5"#, Some(200), 2, || {
+            (
+                offchain::fetch_quotes(PRICE_SOURCE_URL.as_bytes()),
+                Oracle::fetch_and_submit_prices(),
+            )
+        });
+
+    assert_eq!(quotes, Err(http::Error::Unknown));
+    assert_eq!(submitted, Err("Failed to fetch prices"));
+    assert!(transactions.is_empty());
+}
+
+#[test]
+fn non_200_status_submits_nothing() {
+    let ((quotes, submitted), transactions) =
+        with_price_source(br#"{"btc": 31250.5}"#, Some(500), 2, || {
+            (
+                offchain::fetch_quotes(PRICE_SOURCE_URL.as_bytes()),
+                Oracle::fetch_and_submit_prices(),
+            )
+        });
+
+    assert_eq!(quotes, Err(http::Error::Unknown));
+    assert_eq!(submitted, Err("Failed to fetch prices"));
+    assert!(transactions.is_empty());
+}
+
+#[test]
+fn timeout_submits_nothing() {
+    let ((quotes, submitted), transactions) =
+        with_price_source(br#"{"btc": 31250.5}"#, None, 2, || {
+            (
+                offchain::fetch_quotes(PRICE_SOURCE_URL.as_bytes()),
+                Oracle::fetch_and_submit_prices(),
+            )
+        });
+
+    assert_eq!(quotes, Err(http::Error::DeadlineReached));
+    assert_eq!(submitted, Err("Failed to fetch prices"));
+    assert!(transactions.is_empty());
+}
+
+#[test]
+fn feed_prices_requires_feeder() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Oracle::feed_prices(
+                Origin::signed(AccountId::new([1; 32])),
+                vec![(btc(), Price::from(31_000))],
+            ),
+            Error::<Test>::NotFeeder
+        );
+    });
+}
+
+#[test]
+fn exchange_rounds_as_requested() {
+    new_test_ext().execute_with(|| {
+        let usd = assets::usd();
+        // 1 usd is 1/30000 btc
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 1, Rounding::Down),
+            Ok(0)
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 1, Rounding::Up),
+            Ok(1)
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 1, Rounding::Nearest),
+            Ok(0)
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 14_999, Rounding::Nearest),
+            Ok(0)
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 15_000, Rounding::Nearest),
+            Ok(1)
+        );
+        // Exact results are never rounded
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 60_000, Rounding::Up),
+            Ok(2)
+        );
+        assert_eq!(Oracle::exchange(&btc(), &usd, 2), Ok(60_000));
+    });
+}
+
+#[test]
+fn exchange_handles_extreme_amounts() {
+    new_test_ext().execute_with(|| {
+        let usd = assets::usd();
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &usd, u128::MAX, Rounding::Up),
+            Ok(u128::MAX)
+        );
+        // The product overflows u128, the 256-bit intermediate doesn't
+        assert_eq!(
+            Oracle::exchange_with_rounding(&btc(), &btc(), u128::MAX, Rounding::Down),
+            Ok(u128::MAX)
+        );
+        // Rounding at the upper boundary
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &assets::tok(), u128::MAX, Rounding::Down),
+            Ok(u128::MAX / 2)
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &assets::tok(), u128::MAX, Rounding::Up),
+            Ok(u128::MAX / 2 + 1)
+        );
+        // The result doesn't fit into the balance
+        assert_eq!(
+            Oracle::exchange_with_rounding(&assets::tok(), &usd, u128::MAX, Rounding::Down),
+            Err(Error::<Test>::Math.into())
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&btc(), &usd, u128::MAX / 2, Rounding::Up),
+            Err(Error::<Test>::Math.into())
+        );
+    });
+}
+
+#[test]
+fn exchange_with_zero_price() {
+    new_test_ext().execute_with(|| {
+        let usd = assets::usd();
+        assert_noop!(
+            Oracle::force_set_price(Origin::root(), btc(), Price::zero()),
+            Error::<Test>::SetZeroPrice
+        );
+
+        // Only reachable through storage, e.g. after a faulty migration
+        Prices::<Test>::insert(2, Price::zero());
+        assert_eq!(
+            Oracle::exchange_with_rounding(&usd, &btc(), 1, Rounding::Up),
+            Err(Error::<Test>::Math.into())
+        );
+        assert_eq!(
+            Oracle::exchange_with_rounding(&btc(), &usd, u128::MAX, Rounding::Up),
+            Ok(0)
+        );
+    });
+}
+
+#[test]
+fn scheduled_prices_bypass_circuit_breaker() {
+    new_test_ext().execute_with(|| {
+        let run_to = |n| {
+            for block in System::block_number() + 1..=n {
+                System::set_block_number(block);
+                Oracle::on_initialize(block);
+            }
+        };
+
+        run_to(2);
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(30_000)));
+
+        // +100% is applied exactly at the scheduled block
+        run_to(3);
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(60_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), None);
+        System::assert_last_event(Event::Oracle(crate::Event::UpdatePrice {
+            asset: btc(),
+            price: Price::from(60_000),
+        }));
+
+        // The periodic source is still gated, the schedule wins over it
+        run_to(5);
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(60_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), Some(PeriodicPrice::get()));
+        assert_eq!(Prices::<Test>::get(0), Some(Price::from(8)));
+        assert_eq!(PendingPrices::<Test>::get(0), None);
+    });
+}
+
+#[test]
+fn circuit_breaker_limits_change_per_block() {
+    new_test_ext().execute_with(|| {
+        let feeder = AccountId::new([1; 32]);
+        Feeders::<Test>::insert(&feeder, ());
+
+        // +40% twice would compound to +96% within the block
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder.clone()),
+            vec![(btc(), Price::from(42_000))],
+        ));
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder.clone()),
+            vec![(btc(), Price::from(58_800))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(42_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), Some(Price::from(58_800)));
+        System::assert_last_event(Event::Oracle(crate::Event::PriceRejected {
+            asset: btc(),
+            price: Price::from(58_800),
+            last_price: Price::from(30_000),
+        }));
+
+        // Repeated entries of a single call are checked against the same price
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder.clone()),
+            vec![(btc(), Price::from(40_000)), (btc(), Price::from(50_000))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(40_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), Some(Price::from(50_000)));
+
+        // The next block is checked against the price it starts with
+        System::set_block_number(2);
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder),
+            vec![(btc(), Price::from(56_000))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(56_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), None);
+    });
+}
+
+#[test]
+fn circuit_breaker_limits_drops() {
+    new_test_ext().execute_with(|| {
+        let feeder = AccountId::new([1; 32]);
+        Feeders::<Test>::insert(&feeder, ());
+
+        // -40% is +66% measured from the new price
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder.clone()),
+            vec![(btc(), Price::from(18_000))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(30_000)));
+        assert_eq!(PendingPrices::<Test>::get(2), Some(Price::from(18_000)));
+
+        // A crash never gets through
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder.clone()),
+            vec![(btc(), Price::saturating_from_rational(1, 1_000))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(30_000)));
+
+        assert_ok!(Oracle::feed_prices(
+            Origin::signed(feeder),
+            vec![(btc(), Price::from(21_000))],
+        ));
+        assert_eq!(Prices::<Test>::get(2), Some(Price::from(21_000)));
+    });
+}
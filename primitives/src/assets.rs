@@ -1,4 +1,4 @@
-use frame_support::pallet_prelude::*;
+use frame_support::{pallet_prelude::*, traits::ConstU32, weights::Weight};
 use serde::*;
 use sp_std::{fmt, prelude::*};
 extern crate alloc;
@@ -6,22 +6,31 @@ extern crate alloc;
 use alloc::string::String;
 
 const MAX_ASSET_ID_LEN: u32 = 8;
+const MAX_ASSET_NAME_LEN: u32 = 32;
+const MAX_ASSET_SYMBOL_LEN: u32 = 8;
+
+pub type AssetName = BoundedVec<u8, ConstU32<MAX_ASSET_NAME_LEN>>;
+pub type AssetSymbol = BoundedVec<u8, ConstU32<MAX_ASSET_SYMBOL_LEN>>;
+/// Compact index assigned to an asset on creation, used as a storage key by other pallets.
+pub type AssetIndex = u32;
 
 pub fn tok() -> AssetId {
-    unsafe { AssetId::new_unchecked(b"coin") }
+    AssetId::new(*b"coin").expect("static value is valid; qed")
 }
 
 pub fn usd() -> AssetId {
-    unsafe { AssetId::new_unchecked(b"usd") }
+    AssetId::new(*b"usd").expect("static value is valid; qed")
 }
 
+/// Asset symbol of 1 to 8 printable ASCII characters, checked on construction and decoding.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Clone, Decode, Encode, PartialEq, Eq, PartialOrd, Ord, scale_info::TypeInfo)]
-pub struct AssetId(Vec<u8>);
+#[cfg_attr(feature = "std", serde(try_from = "Vec<u8>", into = "Vec<u8>"))]
+#[derive(Clone, Encode, MaxEncodedLen, PartialEq, Eq, scale_info::TypeInfo)]
+pub struct AssetId(BoundedVec<u8, ConstU32<MAX_ASSET_ID_LEN>>);
 
 impl fmt::Debug for AssetId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.0)
+        write!(f, "{:?}", &self.0[..])
     }
 }
 
@@ -30,20 +39,28 @@ impl fmt::Display for AssetId {
         write!(
             f,
             "${}",
-            String::from_utf8(self.0.clone()).unwrap_or_else(|_| String::from("UNKNOWN"))
+            String::from_utf8(self.0.to_vec()).unwrap_or_else(|_| String::from("UNKNOWN"))
         )
     }
 }
 
-impl AssetId {
-    pub unsafe fn new_unchecked(inner: &[u8]) -> Self {
-        Self(inner.to_vec())
+impl PartialOrd for AssetId {
+    fn partial_cmp(&self, other: &Self) -> Option<sp_std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AssetId {
+    fn cmp(&self, other: &Self) -> sp_std::cmp::Ordering {
+        self.0[..].cmp(&other.0[..])
     }
+}
 
+impl AssetId {
     pub fn new(inner: impl IntoIterator<Item = u8>) -> Option<Self> {
         let inner: Vec<u8> = inner.into_iter().collect();
-        if inner.len() as u32 <= MAX_ASSET_ID_LEN {
-            Some(Self(inner))
+        if Self::is_valid(&inner) {
+            inner.try_into().ok().map(Self)
         } else {
             None
         }
@@ -52,31 +69,119 @@ impl AssetId {
     pub fn from_utf8(inner: &str) -> Option<Self> {
         Self::new(inner.bytes())
     }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn is_valid(inner: &[u8]) -> bool {
+        !inner.is_empty()
+            && inner.len() as u32 <= MAX_ASSET_ID_LEN
+            && inner.iter().all(u8::is_ascii_graphic)
+    }
 }
 
-impl MaxEncodedLen for AssetId {
-    fn max_encoded_len() -> usize {
-        u32::max_encoded_len() + MAX_ASSET_ID_LEN as usize
+impl Decode for AssetId {
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        // Check the length before reading, so overlong ids are never allocated
+        let len = <codec::Compact<u32>>::decode(input)?.0;
+        if len > MAX_ASSET_ID_LEN {
+            return Err("AssetId is too long".into());
+        }
+        let mut inner = Vec::new();
+        inner.resize(len as usize, 0u8);
+        input.read(&mut inner)?;
+        Self::new(inner).ok_or_else(|| "Invalid AssetId".into())
     }
 }
 
 impl TryFrom<Vec<u8>> for AssetId {
-    type Error = ();
+    type Error = &'static str;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        Self::new(value).ok_or(())
+        Self::new(value).ok_or("Invalid AssetId")
+    }
+}
+
+impl From<AssetId> for Vec<u8> {
+    fn from(id: AssetId) -> Self {
+        id.0.into_inner()
     }
 }
 
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(
+    Clone, Copy, Debug, Decode, Encode, MaxEncodedLen, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum AssetStatus {
+    Active,
+    /// Balances can't be minted, burnt or transferred.
+    Frozen,
+    /// Dependent storage is being cleaned up, the asset is removed once it's done.
+    Destroying,
+}
+
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Decode, Encode, MaxEncodedLen, PartialEq, Eq, scale_info::TypeInfo)]
 pub struct AssetData {
     pub decimals: u8,
+    pub name: AssetName,
+    pub symbol: AssetSymbol,
+    pub min_balance: u128,
+    pub status: AssetStatus,
+}
+
+impl AssetData {
+    pub fn new(name: &[u8], symbol: &[u8], decimals: u8, min_balance: u128) -> Option<Self> {
+        Some(Self {
+            decimals,
+            name: name.to_vec().try_into().ok()?,
+            symbol: symbol.to_vec().try_into().ok()?,
+            min_balance,
+            status: AssetStatus::Active,
+        })
+    }
+}
+
+/// Access to the parts of asset data other pallets and extrinsics rely on.
+pub trait AssetMetadata {
+    fn set_metadata(&mut self, name: AssetName, symbol: AssetSymbol, decimals: u8);
+
+    fn min_balance(&self) -> u128;
+
+    fn status(&self) -> AssetStatus;
+
+    fn set_status(&mut self, status: AssetStatus);
+}
+
+impl AssetMetadata for AssetData {
+    fn set_metadata(&mut self, name: AssetName, symbol: AssetSymbol, decimals: u8) {
+        self.name = name;
+        self.symbol = symbol;
+        self.decimals = decimals;
+    }
+
+    fn min_balance(&self) -> u128 {
+        self.min_balance
+    }
+
+    fn status(&self) -> AssetStatus {
+        self.status
+    }
+
+    fn set_status(&mut self, status: AssetStatus) {
+        self.status = status;
+    }
 }
 
 pub trait AssetGetter {
-    type AssetId: Parameter + Member + Ord + MaybeSerializeDeserialize + MaxEncodedLen;
-    type AssetData: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
+    type AssetId: Parameter
+        + Member
+        + Ord
+        + MaybeSerializeDeserialize
+        + MaxEncodedLen
+        + TryFrom<Vec<u8>>;
+    type AssetData: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen + AssetMetadata;
     type AssetError;
 
     fn get(id: &Self::AssetId) -> Result<Self::AssetData, Self::AssetError>;
@@ -85,15 +190,36 @@ pub trait AssetGetter {
 
     fn get_all() -> Vec<Self::AssetId>;
 
+    /// All assets sorted by their `Ord` implementation.
     fn get_all_in_ord() -> Vec<Self::AssetId> {
         let mut assets = Self::get_all();
-        assets.sort_by_key(|a| a.encode());
+        assets.sort();
         assets
     }
 
     fn check(id: &Self::AssetId) -> Result<(), Self::AssetError> {
         Self::get(id).map(|_| ())
     }
+
+    /// Checks that the asset exists and its balances can be changed.
+    fn check_active(id: &Self::AssetId) -> Result<(), Self::AssetError>;
+
+    fn index_of(id: &Self::AssetId) -> Result<AssetIndex, Self::AssetError>;
+
+    fn id_of(index: AssetIndex) -> Result<Self::AssetId, Self::AssetError>;
+}
+
+/// Accounts managing an asset on behalf of root.
+pub trait AssetTeam<AccountId, AssetId> {
+    fn is_owner(who: &AccountId, asset: &AssetId) -> bool;
+
+    fn is_issuer(who: &AccountId, asset: &AssetId) -> bool;
+
+    fn is_freezer(who: &AccountId, asset: &AssetId) -> bool;
+
+    /// Whether the asset was created by an account rather than root. Stays true while
+    /// a removed asset is cleaned up, when the team roles above are already dropped.
+    fn is_owned(asset: &AssetId) -> bool;
 }
 
 pub trait OnAssetCreate<AssetId> {
@@ -107,3 +233,72 @@ impl<AssetId> OnAssetCreate<AssetId> for Tuple {
         Some(())
     }
 }
+
+pub trait OnAssetRemove<AssetId> {
+    /// Processes at most `limit` storage entries of the removed `asset`.
+    /// Returns the number of entries processed, whether cleanup is complete and the weight used.
+    /// Called every block until all handlers are complete, so a complete handler must
+    /// return `(0, true, _)` without doing the work again.
+    fn on_asset_remove(asset: &AssetId, limit: u32) -> (u32, bool, Weight);
+
+    /// Called once all handlers are complete, right before the asset is removed.
+    /// Returns the weight used.
+    fn on_asset_removed(_asset: &AssetId) -> Weight {
+        0
+    }
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+impl<AssetId> OnAssetRemove<AssetId> for Tuple {
+    fn on_asset_remove(asset: &AssetId, limit: u32) -> (u32, bool, Weight) {
+        let mut removed = 0u32;
+        let mut weight: Weight = 0;
+        for_tuples!(#(
+            let (count, done, used) = Tuple::on_asset_remove(asset, limit.saturating_sub(removed));
+            removed = removed.saturating_add(count);
+            weight = weight.saturating_add(used);
+            if !done {
+                return (removed, false, weight);
+            }
+        )*);
+        (removed, true, weight)
+    }
+
+    fn on_asset_removed(asset: &AssetId) -> Weight {
+        let mut weight: Weight = 0;
+        for_tuples!(#( weight = weight.saturating_add(Tuple::on_asset_removed(asset)); )*);
+        weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_id_decodes_valid_ids() {
+        let encoded = b"btc".to_vec().encode();
+        assert_eq!(
+            AssetId::decode(&mut &encoded[..]).ok(),
+            AssetId::from_utf8("btc")
+        );
+    }
+
+    #[test]
+    fn asset_id_rejects_overlong_ids() {
+        let encoded = b"verylongid".to_vec().encode();
+        assert!(AssetId::decode(&mut &encoded[..]).is_err());
+
+        // A huge length prefix is rejected before the input is read
+        let encoded = codec::Compact(u32::MAX).encode();
+        assert!(AssetId::decode(&mut &encoded[..]).is_err());
+    }
+
+    #[test]
+    fn asset_id_rejects_invalid_characters() {
+        let encoded = b"b c".to_vec().encode();
+        assert!(AssetId::decode(&mut &encoded[..]).is_err());
+        let encoded = Vec::<u8>::new().encode();
+        assert!(AssetId::decode(&mut &encoded[..]).is_err());
+    }
+}
@@ -1,4 +1,13 @@
-use frame_support::pallet_prelude::*;
+use frame_support::{pallet_prelude::*, traits::LockIdentifier};
+use sp_std::prelude::*;
+
+/// Free balances read in a bounded chunk. `cursor` is the raw storage key to resume
+/// from, `None` once iteration is complete.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BalancesPage<AccountId, AssetId, Balance> {
+    pub balances: Vec<(AccountId, AssetId, Balance)>,
+    pub cursor: Option<Vec<u8>>,
+}
 
 pub trait Currency {
     type AccountId: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
@@ -6,12 +15,16 @@ pub trait Currency {
     type AssetId: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
     type CurrencyError;
 
+    /// Pays `amount` to `who` out of treasury. Total issuance doesn't change, unlike
+    /// the issuer `mint` call of the balances pallet that creates new supply.
     fn mint(
         who: &Self::AccountId,
         asset: &Self::AssetId,
         amount: Self::Balance,
     ) -> Result<(), Self::CurrencyError>;
 
+    /// Moves `amount` from `who` to treasury. Total issuance doesn't change, unlike
+    /// the issuer `burn` call of the balances pallet that destroys supply.
     fn burn(
         who: &Self::AccountId,
         asset: &Self::AssetId,
@@ -27,9 +40,118 @@ pub trait Currency {
 
     fn balance(who: &Self::AccountId, asset: &Self::AssetId) -> Self::Balance;
 
+    fn reserved_balance(who: &Self::AccountId, asset: &Self::AssetId) -> Self::Balance;
+
+    /// Moves `amount` from free to reserved balance.
+    fn reserve(
+        who: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Moves `amount` from reserved back to free balance. Allowed in frozen assets,
+    /// so deposits can be released.
+    fn unreserve(
+        who: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Moves `amount` of `from` reserved balance to `to` reserved balance. Allowed in
+    /// frozen assets, like `unreserve`.
+    fn repatriate_reserved(
+        from: &Self::AccountId,
+        to: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Takes `amount` of reserved balance to treasury.
+    fn slash_reserved(
+        who: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Creates or updates lock `id`, free balance can't go below the largest lock.
+    fn set_lock(
+        id: LockIdentifier,
+        who: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    fn remove_lock(
+        id: LockIdentifier,
+        who: &Self::AccountId,
+        asset: &Self::AssetId,
+    ) -> Result<(), Self::CurrencyError>;
+
+    fn allowance(
+        owner: &Self::AccountId,
+        spender: &Self::AccountId,
+        asset: &Self::AssetId,
+    ) -> Self::Balance;
+
+    /// Allows `spender` to transfer up to `amount` of `owner` balance, replacing
+    /// the previous approval.
+    fn approve(
+        owner: &Self::AccountId,
+        spender: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Transfers `amount` of `owner` balance approved to `spender`.
+    fn transfer_from(
+        spender: &Self::AccountId,
+        owner: &Self::AccountId,
+        to: &Self::AccountId,
+        asset: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<(), Self::CurrencyError>;
+
+    fn cancel_approval(
+        owner: &Self::AccountId,
+        spender: &Self::AccountId,
+        asset: &Self::AssetId,
+    ) -> Result<(), Self::CurrencyError>;
+
+    /// Whether `who` is frozen in the asset: it can still receive, but not send or burn.
+    fn is_frozen(who: &Self::AccountId, asset: &Self::AssetId) -> bool;
+
+    /// Sum of all balances of the asset.
+    fn total_issuance(asset: &Self::AssetId) -> Self::Balance;
+
     fn total_in_stable(who: &Self::AccountId) -> Self::Balance;
 
-    type BalancesIter: Iterator<Item = (Self::AccountId, Self::AssetId, Self::Balance)>;
+    /// Up to `limit` balances of `who`, starting after `cursor`. A zero `limit` returns
+    /// an empty page without a cursor.
+    fn account_balances(
+        who: &Self::AccountId,
+        cursor: Option<Vec<u8>>,
+        limit: u32,
+    ) -> BalancesPage<Self::AccountId, Self::AssetId, Self::Balance>;
+
+    /// Up to `limit` balances of `asset`, starting after `cursor`. A zero `limit` returns
+    /// an empty page without a cursor.
+    fn asset_balances(
+        asset: &Self::AssetId,
+        cursor: Option<Vec<u8>>,
+        limit: u32,
+    ) -> BalancesPage<Self::AccountId, Self::AssetId, Self::Balance>;
+}
+
+/// Notified by `Currency` implementations on every balance mutation.
+pub trait OnBalanceChanged<AccountId, AssetId, Balance> {
+    /// `old` and `new` are total (free and reserved) balances, they're equal
+    /// when only the split between free and reserved changed.
+    fn on_balance_changed(who: &AccountId, asset: &AssetId, old: &Balance, new: &Balance);
+}
 
-    fn iter_balances() -> Self::BalancesIter;
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+impl<AccountId, AssetId, Balance> OnBalanceChanged<AccountId, AssetId, Balance> for Tuple {
+    fn on_balance_changed(who: &AccountId, asset: &AssetId, old: &Balance, new: &Balance) {
+        for_tuples!(#( Tuple::on_balance_changed(who, asset, old, new); )*);
+    }
 }
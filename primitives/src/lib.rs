@@ -3,3 +3,4 @@
 pub mod assets;
 pub mod currency;
 pub mod prices;
+pub mod testing;
@@ -1,5 +1,13 @@
 use frame_support::pallet_prelude::*;
 
+/// Rounding applied to the result of a price conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+    Nearest,
+}
+
 pub trait PriceGetter {
     type AssetId: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
     type Balance: Parameter + Member + MaybeSerializeDeserialize + MaxEncodedLen;
@@ -10,12 +18,21 @@ pub trait PriceGetter {
 
     fn base_asset() -> Self::AssetId;
 
-    fn exchange(
+    fn exchange_with_rounding(
         from: &Self::AssetId,
         to: &Self::AssetId,
         amount: Self::Balance,
+        rounding: Rounding,
     ) -> Result<Self::Balance, Self::PriceError>;
 
+    fn exchange(
+        from: &Self::AssetId,
+        to: &Self::AssetId,
+        amount: Self::Balance,
+    ) -> Result<Self::Balance, Self::PriceError> {
+        Self::exchange_with_rounding(from, to, amount, Rounding::Down)
+    }
+
     fn to_stable_amount(
         asset: &Self::AssetId,
         amount: Self::Balance,
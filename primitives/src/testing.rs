@@ -0,0 +1,82 @@
+//! Test doubles shared by pallet mocks.
+
+use crate::{
+    assets::{self, AssetData, AssetGetter, AssetId, AssetIndex},
+    prices::{PriceGetter, Rounding},
+};
+use frame_support::traits::Get;
+use sp_runtime::DispatchError;
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub fn btc() -> AssetId {
+    AssetId::from_utf8("btc").expect("static value is valid; qed")
+}
+
+/// Fixed asset list given by `List`, indexed in its order. The main asset is `tok`,
+/// no asset is ever frozen.
+pub struct TestAssets<List>(PhantomData<List>);
+
+impl<List: Get<Vec<AssetId>>> AssetGetter for TestAssets<List> {
+    type AssetId = AssetId;
+    type AssetData = AssetData;
+    type AssetError = DispatchError;
+
+    fn get(id: &AssetId) -> Result<AssetData, DispatchError> {
+        Self::index_of(id)?;
+        AssetData::new(b"Test asset", b"TST", 9, 0).ok_or(DispatchError::Other("Invalid data"))
+    }
+
+    fn get_main() -> AssetId {
+        assets::tok()
+    }
+
+    fn get_all() -> Vec<AssetId> {
+        List::get()
+    }
+
+    fn check_active(id: &AssetId) -> Result<(), DispatchError> {
+        Self::check(id)
+    }
+
+    fn index_of(id: &AssetId) -> Result<AssetIndex, DispatchError> {
+        Self::get_all()
+            .iter()
+            .position(|asset| asset == id)
+            .map(|index| index as AssetIndex)
+            .ok_or(DispatchError::Other("Unknown asset"))
+    }
+
+    fn id_of(index: AssetIndex) -> Result<AssetId, DispatchError> {
+        Self::get_all()
+            .get(index as usize)
+            .cloned()
+            .ok_or(DispatchError::Other("Unknown asset"))
+    }
+}
+
+/// Every asset is worth 1 of the main asset.
+pub struct UnitPrices;
+
+impl PriceGetter for UnitPrices {
+    type AssetId = AssetId;
+    type Balance = u128;
+    type Price = u128;
+    type PriceError = DispatchError;
+
+    fn get(_id: &AssetId) -> Result<u128, DispatchError> {
+        Ok(1)
+    }
+
+    fn base_asset() -> AssetId {
+        assets::tok()
+    }
+
+    fn exchange_with_rounding(
+        _from: &AssetId,
+        _to: &AssetId,
+        amount: u128,
+        _rounding: Rounding,
+    ) -> Result<u128, DispatchError> {
+        Ok(amount)
+    }
+}
@@ -19,7 +19,7 @@ use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, TrailingZeroInput, Verify},
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, FixedU128, MultiSignature,
+    ApplyExtrinsicResult, ConsensusEngineId, FixedU128, MultiSignature,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -30,7 +30,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
     construct_runtime, parameter_types,
     traits::{
-        ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+        ConstU128, ConstU32, ConstU64, ConstU8, FindAuthor, KeyOwnerProofSystem, Randomness,
+        StorageInfo,
     },
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -217,6 +218,23 @@ parameter_types! {
     pub DistributionModuleId: AccountId = AccountId::decode(&mut TrailingZeroInput::new(b"distribution")).unwrap();
 
     pub const InitialAssetIssuance: Balance = 1_000_000_000_000_000_000_000_000;
+    pub const AssetDeposit: Balance = 100_000_000_000_000;
+    /// 1% of `InitialAssetIssuance`, the rest stays in treasury.
+    pub const OwnerIssuance: Balance = 10_000_000_000_000_000_000_000;
+    pub const AuthorFeeShare: Perbill = Perbill::from_percent(20);
+}
+
+/// Maps the Aura author of a block to its account.
+pub struct AuraAccountAdapter;
+
+impl FindAuthor<AccountId> for AuraAccountAdapter {
+    fn find_author<'a, I>(digests: I) -> Option<AccountId>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        pallet_aura::AuraAuthorId::<Runtime>::find_author(digests)
+            .and_then(|author| AccountId::try_from(AsRef::<[u8]>::as_ref(&author)).ok())
+    }
 }
 
 impl pallet_assets::Config for Runtime {
@@ -225,19 +243,35 @@ impl pallet_assets::Config for Runtime {
     type AssetData = AssetData;
     type MainAsset = TokAsset;
     type OnAssetCreate = (Balances,);
+    // Distribution refunds deposits at the oracle price, so it goes before Oracle
+    type OnAssetRemove = (Distribution, Oracle, Balances);
+    type RemoveLimit = ConstU32<1_000>;
+    type Currency = Balances;
+    type AssetDeposit = AssetDeposit;
+    type OwnerIssuance = OwnerIssuance;
+    type MaxAssets = ConstU32<100>;
 }
 
 impl pallet_balances::Config for Runtime {
     type Event = Event;
     type Balance = Balance;
     type Assets = Assets;
+    type AssetTeam = Assets;
     type TreasuryModuleId = TreasuryModuleId;
     type Prices = Oracle;
     type InitialAssetIssuance = InitialAssetIssuance;
+    type MaxLocks = ConstU32<50>;
+    type MaxBatchTransfers = ConstU32<1_000>;
+    type FindAuthor = AuraAccountAdapter;
+    type AuthorFeeShare = AuthorFeeShare;
+    type OnBalanceChanged = ();
 }
 
 parameter_types! {
     pub const Precision: Price = Price::from_inner(1_000_000);
+    /// 100%, prices may double or halve per block. Random dev prices in range [1.0, 2.0]
+    /// never trip the breaker.
+    pub const MaxPriceChange: Price = Price::from_inner(1_000_000_000_000_000_000);
 }
 
 impl pallet_oracle::Config for Runtime {
@@ -246,14 +280,22 @@ impl pallet_oracle::Config for Runtime {
     type Price = Price;
     type Assets = Assets;
     type StableAsset = UsdAsset;
-    type Randomness = RandomnessCollectiveFlip;
-    type Precision = Precision;
+    // Random prices are generated on chains with `random_prices` set in genesis only
+    type PriceSource = (
+        pallet_oracle::ScheduledPrice<Runtime>,
+        pallet_oracle::RandomPrice<Runtime, RandomnessCollectiveFlip, Precision>,
+    );
+    type UpdatePeriod = ConstU32<5>;
+    type MaxPriceChange = MaxPriceChange;
+    type MaxScheduledPrices = ConstU32<100>;
+    type AuthorityId = pallet_oracle::crypto::OracleAuthId;
 }
 
 impl pallet_distribution::Config for Runtime {
     type Event = Event;
     type Balance = Balance;
     type Assets = Assets;
+    type AssetTeam = Assets;
     type Currency = Balances;
     type ModuleId = DistributionModuleId;
     type Prices = Oracle;
@@ -323,7 +365,7 @@ where
             frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+            pallet_balances::ChargeAssetTxPayment::<Runtime>::from(0, None),
         );
 
         let raw_payload = SignedPayload::new(call, extra).ok()?;
@@ -346,7 +388,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    pallet_balances::ChargeAssetTxPayment<Runtime>,
 );
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -488,4 +530,34 @@ impl_runtime_apis! {
             TransactionPayment::query_fee_details(uxt, len)
         }
     }
+
+    impl pallet_balances::runtime_api::BalancesApi<Block, AccountId, AssetId, Balance> for Runtime {
+        fn total_issuance(asset: AssetId) -> Balance {
+            <Balances as primitives::currency::Currency>::total_issuance(&asset)
+        }
+
+        fn circulating_supply(asset: AssetId) -> Balance {
+            Balances::circulating_supply(&asset)
+        }
+
+        fn holder_count(asset: AssetId) -> u32 {
+            Balances::holder_count(&asset)
+        }
+
+        fn top_holders(asset: AssetId, limit: u32) -> Vec<(AccountId, Balance)> {
+            Balances::top_holders(&asset, limit)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    impl frame_try_runtime::TryRuntime<Block> for Runtime {
+        fn on_runtime_upgrade() -> (Weight, Weight) {
+            let weight = Executive::try_runtime_upgrade().unwrap();
+            (weight, BlockWeights::get().max_block)
+        }
+
+        fn execute_block_no_check(block: Block) -> Weight {
+            Executive::execute_block_no_check(block)
+        }
+    }
 }
@@ -15,7 +15,14 @@ import {
   getAsset,
   removeAsset,
 } from "./assets";
-import { burn, getBalances, mint, transfer } from "./balances";
+import {
+  burn,
+  getBalance,
+  getBalances,
+  mint,
+  transfer,
+  transferBatch,
+} from "./balances";
 import { deposit, issue, issueSudo, withdraw } from "./distribution";
 import { HackathonKeyring } from "./keyring";
 
@@ -50,6 +57,10 @@ export interface HackathonApi extends ApiPromise {
     currency: string,
     amount: bigint
   ): Promise<void>;
+  transferBatch(
+    from: AddressOrPair,
+    transfers: { to: string; currency: string; amount: bigint }[]
+  ): Promise<void>;
   mint(
     keyring: HackathonKeyring,
     who: string,
@@ -196,6 +207,12 @@ export function wrapApi(api: ApiPromise): HackathonApi {
     ): Promise<void> {
       await transfer(this, from, to, currency, amount);
     },
+    async transferBatch(
+      from: AddressOrPair,
+      transfers: { to: string; currency: string; amount: bigint }[]
+    ): Promise<void> {
+      await transferBatch(this, from, transfers);
+    },
     async mint(
       keyring: HackathonKeyring,
       who: string,
@@ -213,7 +230,7 @@ export function wrapApi(api: ApiPromise): HackathonApi {
       await burn(this, keyring, who, currency, amount);
     },
     async getBalance(who: string, currency: string): Promise<bigint> {
-      return (await api.query.balances.accounts(who, currency)).toBigInt();
+      return await getBalance(this, who, currency);
     },
     async getBalances(who: string): Promise<{ [currency: string]: bigint }> {
       return await getBalances(this, who);
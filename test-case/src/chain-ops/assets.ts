@@ -36,6 +36,30 @@ export async function getAsset(
   }
 }
 
+export async function getAssetIndex(
+  api: Api,
+  name: AssetId
+): Promise<number | undefined> {
+  const maybeValue = await api.query.assets.indices(name);
+  if (maybeValue.isSome) {
+    return maybeValue.unwrap().toNumber();
+  } else {
+    return undefined;
+  }
+}
+
+export async function getAssetId(
+  api: Api,
+  index: number
+): Promise<AssetId | undefined> {
+  const maybeValue = await api.query.assets.idByIndex(index);
+  if (maybeValue.isSome) {
+    return maybeValue.unwrap().toHuman() as AssetId;
+  } else {
+    return undefined;
+  }
+}
+
 export async function createAsset(
   api: Api,
   keyring: Keyring,
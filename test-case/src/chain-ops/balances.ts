@@ -1,6 +1,7 @@
 import { AddressOrPair } from "@polkadot/api/types";
 import { logger } from "../logger";
 import { HackathonApi as Api } from "./api";
+import { getAssetId, getAssetIndex } from "./assets";
 import { HackathonKeyring as Keyring } from "./keyring";
 
 export async function getBalances(
@@ -8,12 +9,27 @@ export async function getBalances(
   who: string
 ): Promise<{ [currency: string]: bigint }> {
   let balances: { [currency: string]: bigint } = {};
-  (await api.query.balances.accounts.entries(who)).forEach(
-    ([k, v]) => (balances[(k.toHuman() as string[])[1]] = v.toBigInt())
-  );
+  for (const [k, v] of await api.query.balances.accounts.entries(who)) {
+    const currency = await getAssetId(api, k.args[1].toNumber());
+    if (currency !== undefined) {
+      balances[currency] = v.free.toBigInt();
+    }
+  }
   return balances;
 }
 
+export async function getBalance(
+  api: Api,
+  who: string,
+  currency: string
+): Promise<bigint> {
+  const index = await getAssetIndex(api, currency);
+  if (index === undefined) {
+    return 0n;
+  }
+  return (await api.query.balances.accounts(who, index)).free.toBigInt();
+}
+
 export async function transfer(
   api: Api,
   from: AddressOrPair,
@@ -28,6 +44,20 @@ export async function transfer(
   logger.trace(result);
 }
 
+export async function transferBatch(
+  api: Api,
+  from: AddressOrPair,
+  transfers: { to: string; currency: string; amount: bigint }[]
+): Promise<void> {
+  const result = await api.signAndWatch(
+    api.tx.balances.transferBatch(
+      transfers.map(({ to, currency, amount }) => [to, currency, amount])
+    ),
+    from
+  );
+  logger.trace(result);
+}
+
 export async function mint(
   api: Api,
   keyring: Keyring,
@@ -1,6 +1,7 @@
 import { AddressOrPair } from "@polkadot/api/types";
 import { logger } from "../logger";
 import { HackathonApi as Api } from "./api";
+import { getAssetIndex } from "./assets";
 import { HackathonKeyring as Keyring } from "./keyring";
 
 export async function getDeposit(
@@ -8,7 +9,11 @@ export async function getDeposit(
   who: string,
   currency: string
 ): Promise<bigint> {
-  return (await api.query.distribution.deposits(who, currency)).toBigInt();
+  const index = await getAssetIndex(api, currency);
+  if (index === undefined) {
+    return 0n;
+  }
+  return (await api.query.distribution.deposits(who, index)).toBigInt();
 }
 
 export async function deposit(
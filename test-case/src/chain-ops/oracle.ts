@@ -1,12 +1,17 @@
 import { logger } from "../logger";
 import { HackathonApi as Api } from "./api";
+import { getAssetIndex } from "./assets";
 import { HackathonKeyring as Keyring } from "./keyring";
 
 export async function getPrice(
   api: Api,
   currency: string
 ): Promise<bigint | undefined> {
-  const maybeValue = await api.query.oracle.prices(currency);
+  const index = await getAssetIndex(api, currency);
+  if (index === undefined) {
+    return undefined;
+  }
+  const maybeValue = await api.query.oracle.prices(index);
   if (maybeValue.isSome) {
     return maybeValue.unwrap().toBigInt();
   } else {
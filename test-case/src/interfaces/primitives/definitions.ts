@@ -3,8 +3,15 @@
 export default {
   types: {
     AssetId: "Vec<u8>",
+    AssetStatus: {
+      _enum: ["Active", "Frozen", "Destroying"],
+    },
     AssetData: {
       decimals: "u8",
+      name: "BoundedVec<u8, ConstU32<32>>",
+      symbol: "BoundedVec<u8, ConstU32<8>>",
+      minBalance: "u128",
+      status: "AssetStatus",
     },
     Balance: "u128",
     Price: "FixedU128",